@@ -123,6 +123,34 @@ pub enum TirEncoding {
     Base64,
 }
 
+/// Error parsing a [`TirEncoding`] from a string.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown encoding `{0}`, expected `hex` or `base64`")]
+pub struct UnknownEncoding(pub String);
+
+impl std::str::FromStr for TirEncoding {
+    type Err = UnknownEncoding;
+
+    /// Parses `hex` or `base64`, ignoring case and surrounding whitespace.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "hex" => Ok(TirEncoding::Hex),
+            "base64" => Ok(TirEncoding::Base64),
+            _ => Err(UnknownEncoding(s.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for TirEncoding {
+    /// Writes the same lowercase name used on the wire.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TirEncoding::Hex => f.write_str("hex"),
+            TirEncoding::Base64 => f.write_str("base64"),
+        }
+    }
+}
+
 /// An envelope containing Transaction Intermediate Representation (TIR) data.
 ///
 /// TIR is the intermediate format used by TX3 to represent transactions in a
@@ -157,3 +185,136 @@ pub struct TirEnvelope {
     /// The TIR specification version.
     pub version: String,
 }
+
+/// Maximum length of a Cardano asset name, in bytes.
+pub const MAX_ASSET_NAME_BYTES: usize = 32;
+
+/// Length of a Cardano policy id (a script hash), in bytes.
+pub const POLICY_ID_BYTES: usize = 28;
+
+/// Error validating an [`AssetName`] or an [`AssetArg`] policy.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AssetError {
+    /// A `Hex` asset name isn't valid hex.
+    #[error("name `{0}` is not valid hex")]
+    NameNotHex(String),
+
+    /// The asset name is longer than [`MAX_ASSET_NAME_BYTES`].
+    #[error("name is {0} bytes long, the limit is {MAX_ASSET_NAME_BYTES}")]
+    NameTooLong(usize),
+
+    /// The policy id isn't valid hex.
+    #[error("policy `{0}` is not valid hex")]
+    PolicyNotHex(String),
+
+    /// The policy id isn't [`POLICY_ID_BYTES`] long.
+    #[error("policy is {0} bytes long, expected {POLICY_ID_BYTES}")]
+    PolicyLength(usize),
+}
+
+/// An asset name tagged with the encoding it was written in.
+///
+/// Asset names are raw bytes on-chain, but people write them either as a
+/// human-readable UTF-8 string (e.g. `"HOSKY"`) or as the hex of those bytes
+/// (e.g. `"484f534b59"`). Tagging the encoding explicitly removes the guess.
+///
+/// # Example
+///
+/// ```ignore
+/// use tx3_sdk::core::AssetName;
+///
+/// let name = AssetName::Utf8("HOSKY".to_string());
+/// assert_eq!(name.to_hex().unwrap(), "484f534b59");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetName {
+    /// A human-readable name, encoded to bytes as UTF-8.
+    Utf8(String),
+    /// The hex encoding of the raw name bytes.
+    Hex(String),
+}
+
+impl AssetName {
+    /// Returns the raw bytes of the name, checking the length limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AssetError::NameNotHex` if a `Hex` name isn't valid hex, or
+    /// `AssetError::NameTooLong` if the name is longer than
+    /// [`MAX_ASSET_NAME_BYTES`].
+    pub fn to_bytes(&self) -> Result<Vec<u8>, AssetError> {
+        let bytes = match self {
+            AssetName::Utf8(name) => name.as_bytes().to_vec(),
+            AssetName::Hex(name) => {
+                hex::decode(name).map_err(|_| AssetError::NameNotHex(name.clone()))?
+            }
+        };
+
+        if bytes.len() > MAX_ASSET_NAME_BYTES {
+            return Err(AssetError::NameTooLong(bytes.len()));
+        }
+
+        Ok(bytes)
+    }
+
+    /// Returns the name as lowercase hex, the form the resolver expects.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`AssetName::to_bytes`].
+    pub fn to_hex(&self) -> Result<String, AssetError> {
+        self.to_bytes().map(hex::encode)
+    }
+}
+
+/// A structured asset value for `AnyAsset` args.
+///
+/// Normalized by `Invocation::with_asset_arg` into the JSON object the
+/// resolver expects: `{ "policy": <hex>, "name": <hex>, "amount": <int> }`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetArg {
+    /// Hex-encoded policy id (empty for ADA).
+    pub policy: String,
+    /// The asset name, tagged with its encoding.
+    pub name: AssetName,
+    /// Amount of the asset.
+    pub amount: u64,
+}
+
+impl AssetArg {
+    /// Checks the policy id is empty (ADA) or a 28-byte hex script hash.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AssetError::PolicyNotHex` or `AssetError::PolicyLength`.
+    pub fn check_policy(&self) -> Result<(), AssetError> {
+        let bytes =
+            hex::decode(&self.policy).map_err(|_| AssetError::PolicyNotHex(self.policy.clone()))?;
+
+        if !bytes.is_empty() && bytes.len() != POLICY_ID_BYTES {
+            return Err(AssetError::PolicyLength(bytes.len()));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tir_encoding_round_trips_through_strings() {
+        for encoding in [TirEncoding::Hex, TirEncoding::Base64] {
+            let parsed: TirEncoding = encoding.to_string().parse().unwrap();
+            assert_eq!(parsed.to_string(), encoding.to_string());
+        }
+
+        assert!(matches!(" HEX ".parse(), Ok(TirEncoding::Hex)));
+        assert!(matches!("Base64".parse(), Ok(TirEncoding::Base64)));
+        assert_eq!(
+            "bech32".parse::<TirEncoding>().unwrap_err(),
+            UnknownEncoding("bech32".to_string())
+        );
+    }
+}
@@ -360,6 +360,7 @@ pub struct Tx3ClientBuilder {
     known_parties: HashSet<String>,
     trp_options: Option<trp::ClientOptions>,
     profile: Option<String>,
+    default_profile: Option<String>,
     parties: HashMap<String, Party>,
     unchecked_parties: HashMap<String, Party>,
     env_overrides: EnvMap,
@@ -387,6 +388,7 @@ impl Tx3ClientBuilder {
             known_parties,
             trp_options: None,
             profile: None,
+            default_profile: None,
             parties: HashMap::new(),
             unchecked_parties: HashMap::new(),
             env_overrides: EnvMap::new(),
@@ -418,7 +420,9 @@ impl Tx3ClientBuilder {
 
         let known_parties = protocol.parties().keys().cloned().collect();
 
-        Self::from_parts(transactions, profiles, known_parties)
+        let mut out = Self::from_parts(transactions, profiles, known_parties);
+        out.default_profile = protocol.default_profile().map(str::to_string);
+        out
     }
 
     /// Sets the full TRP client options.
@@ -433,6 +437,7 @@ impl Tx3ClientBuilder {
         self.trp_options = Some(trp::ClientOptions {
             endpoint: url.into(),
             headers: None,
+            ..Default::default()
         });
         self
     }
@@ -444,6 +449,7 @@ impl Tx3ClientBuilder {
         let opts = self.trp_options.get_or_insert_with(|| trp::ClientOptions {
             endpoint: String::new(),
             headers: None,
+            ..Default::default()
         });
         opts.headers
             .get_or_insert_with(HashMap::new)
@@ -452,11 +458,21 @@ impl Tx3ClientBuilder {
     }
 
     /// Selects a profile by name. Validated in `build()`.
+    ///
+    /// Without one, the protocol's [`Protocol::default_profile`] is used, as
+    /// in [`Protocol::invoke`].
     pub fn with_profile(mut self, name: impl Into<String>) -> Self {
         self.profile = Some(name.into());
         self
     }
 
+    /// Applies no profile, not even the protocol's default one.
+    pub fn without_profile(mut self) -> Self {
+        self.profile = None;
+        self.default_profile = None;
+        self
+    }
+
     /// Binds a party (signer or read-only address) by name. Validated in
     /// `build()` against the protocol's declared parties.
     pub fn with_party(mut self, name: impl Into<String>, party: Party) -> Self {
@@ -516,7 +532,7 @@ impl Tx3ClientBuilder {
             return Err(Error::MissingTrpEndpoint);
         }
 
-        let selected_profile = match self.profile {
+        let selected_profile = match self.profile.or(self.default_profile) {
             Some(name) => Some(
                 self.profiles
                     .get(&name)
@@ -570,11 +586,7 @@ fn build_resolve_params(
     }
     merged.extend(args);
 
-    ResolveParams {
-        tir,
-        args: merged,
-        env: None,
-    }
+    ResolveParams::new(tir, merged)
 }
 
 /// Builder for transaction invocation.
@@ -1198,12 +1210,14 @@ pub mod signer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_fixtures::hex_tir;
     use crate::trp::{ClientOptions, WitnessType};
 
     fn stub_trp() -> trp::Client {
         trp::Client::new(ClientOptions {
             endpoint: "http://localhost:0/unused".to_string(),
             headers: None,
+            ..Default::default()
         })
     }
 
@@ -1315,12 +1329,29 @@ mod tests {
         assert_eq!(keys, vec!["01", "02", "03"]);
     }
 
-    fn sample_tir() -> TirEnvelope {
-        TirEnvelope {
-            content: "abcd".to_string(),
-            encoding: crate::core::TirEncoding::Hex,
-            version: "v1beta0".to_string(),
-        }
+    #[test]
+    fn builder_applies_the_default_profile() {
+        let mut json: Value =
+            serde_json::from_str(include_str!("../tests/fixtures/transfer.tii")).unwrap();
+        json["tii"]["defaultProfile"] = serde_json::json!("preprod");
+        let protocol = Protocol::from_json(json).unwrap();
+
+        let client = protocol
+            .clone()
+            .client()
+            .trp_endpoint("http://localhost")
+            .build()
+            .unwrap();
+        let profile = client.selected_profile.unwrap();
+        assert_eq!(profile.environment["tax"], serde_json::json!(5000000));
+
+        let client = protocol
+            .client()
+            .trp_endpoint("http://localhost")
+            .without_profile()
+            .build()
+            .unwrap();
+        assert!(client.selected_profile.is_none());
     }
 
     #[test]
@@ -1334,7 +1365,7 @@ mod tests {
         let mut args = ArgMap::new();
         args.insert("quantity".to_string(), serde_json::json!(10_000_000));
 
-        let params = build_resolve_params(sample_tir(), env, &parties, args);
+        let params = build_resolve_params(hex_tir("abcd"), env, &parties, args);
 
         assert_eq!(params.env, None);
         assert_eq!(params.tir.content, "abcd");
@@ -1357,8 +1388,7 @@ mod tests {
         let mut args = ArgMap::new();
         args.insert("quantity".to_string(), serde_json::json!(999));
 
-        let params =
-            build_resolve_params(sample_tir(), env, &HashMap::new(), args);
+        let params = build_resolve_params(hex_tir("abcd"), env, &HashMap::new(), args);
 
         assert_eq!(
             params.args.get("quantity").unwrap(),
@@ -1376,12 +1406,7 @@ mod tests {
         let mut parties = HashMap::new();
         parties.insert("sender".to_string(), Party::signer(stub));
 
-        let params = build_resolve_params(
-            sample_tir(),
-            EnvMap::new(),
-            &parties,
-            ArgMap::new(),
-        );
+        let params = build_resolve_params(hex_tir("abcd"), EnvMap::new(), &parties, ArgMap::new());
 
         assert_eq!(
             params.args.get("sender").unwrap(),
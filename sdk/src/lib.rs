@@ -48,6 +48,11 @@ pub mod facade;
 pub mod tii;
 pub mod trp;
 
+#[cfg(test)]
+mod test_fixtures;
+#[cfg(test)]
+mod test_server;
+
 pub use facade::signer::{CardanoSigner, Ed25519Signer};
 pub use facade::{
     Error, Party, PollConfig, Profile, ResolvedTx, SignRequest, SignedTx, Signer, SubmittedTx,
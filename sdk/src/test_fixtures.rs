@@ -0,0 +1,48 @@
+//! Protocols and TIR envelopes shared by unit tests.
+
+use serde_json::Value;
+
+use crate::core::{TirEncoding, TirEnvelope};
+use crate::tii::{Protocol, ProtocolBuilder};
+
+/// A `v1beta0` TIR envelope over hex `content`.
+///
+/// The content isn't decoded until a test asks for it, so `"00"` stands in
+/// wherever the IR itself doesn't matter.
+pub(crate) fn hex_tir(content: &str) -> TirEnvelope {
+    TirEnvelope {
+        content: content.to_string(),
+        encoding: TirEncoding::Hex,
+        version: "v1beta0".to_string(),
+    }
+}
+
+/// A protocol with a single `tx` transaction taking `params`, over a
+/// placeholder TIR.
+pub(crate) fn protocol_with_params(params: Value) -> ProtocolBuilder {
+    Protocol::builder()
+        .tii_version("v1beta0")
+        .name("fixture")
+        .transaction("tx", hex_tir("00"), params)
+}
+
+/// The `transfer.tii` fixture with its `transfer` tx declaring `params`
+/// instead, over the same TIR, so the schema drifts from the IR.
+pub(crate) fn drifted_transfer(params: Value) -> Protocol {
+    let protocol = Protocol::from_static(include_str!("../tests/fixtures/transfer.tii")).unwrap();
+    let tir = protocol.txs()["transfer"].tir.clone();
+
+    Protocol::builder()
+        .tii_version("v1beta0")
+        .name("drifted")
+        .party("sender", None)
+        .party("receiver", None)
+        .party("middleman", None)
+        .environment(serde_json::json!({
+            "type": "object",
+            "properties": { "tax": { "type": "integer" } }
+        }))
+        .transaction("transfer", tir, params)
+        .build()
+        .unwrap()
+}
@@ -0,0 +1,166 @@
+//! A local HTTP server answering canned responses, for tests.
+
+use std::io::{Read, Write};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+/// How long a [`Reply::hang`] holds the connection open.
+const HANG_FOR: Duration = Duration::from_secs(5);
+
+/// A canned HTTP response.
+///
+/// Every response closes the connection, so a body without a declared
+/// length ends there.
+pub(crate) struct Reply {
+    status: &'static str,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    hang: bool,
+}
+
+impl Reply {
+    /// A response with the given status line (e.g. `"404 Not Found"`) and no
+    /// body.
+    pub(crate) fn new(status: &'static str) -> Self {
+        Reply {
+            status,
+            headers: vec![],
+            body: vec![],
+            hang: false,
+        }
+    }
+
+    /// A `200 OK` with a JSON body.
+    pub(crate) fn json(body: impl Into<String>) -> Self {
+        Reply::new("200 OK")
+            .header("content-type", "application/json")
+            .body(body.into())
+    }
+
+    /// A JSON-RPC response carrying `result`.
+    pub(crate) fn rpc_result(result: Value) -> Self {
+        Reply::json(json!({ "jsonrpc": "2.0", "id": "1", "result": result }).to_string())
+    }
+
+    /// A JSON-RPC error response.
+    pub(crate) fn rpc_error(code: i32, message: &str) -> Self {
+        let error = json!({ "code": code, "message": message });
+        Reply::json(json!({ "jsonrpc": "2.0", "id": "1", "error": error }).to_string())
+    }
+
+    /// Accepts the connection but never answers.
+    pub(crate) fn hang() -> Self {
+        Reply {
+            hang: true,
+            ..Reply::new("200 OK")
+        }
+    }
+
+    pub(crate) fn header(mut self, name: &str, value: impl ToString) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub(crate) fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Declares the body length with a `content-length` header.
+    pub(crate) fn sized(self) -> Self {
+        let len = self.body.len();
+        self.header("content-length", len)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut out = format!("HTTP/1.1 {}\r\nconnection: close\r\n", self.status);
+
+        for (name, value) in &self.headers {
+            out.push_str(&format!("{name}: {value}\r\n"));
+        }
+
+        out.push_str("\r\n");
+
+        let mut out = out.into_bytes();
+        out.extend_from_slice(&self.body);
+        out
+    }
+}
+
+/// A server answering one [`Reply`] per connection, in order.
+///
+/// Once the replies run out the server stops, so further requests fail to
+/// connect.
+pub(crate) struct Server {
+    pub(crate) url: String,
+    requests: Receiver<String>,
+}
+
+impl Server {
+    /// The next request received, lowercased, head and body.
+    pub(crate) fn request(&self) -> String {
+        self.requests.recv_timeout(HANG_FOR).unwrap()
+    }
+}
+
+/// Starts a server on a local port answering `replies` in order.
+pub(crate) fn serve(replies: Vec<Reply>) -> Server {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, requests) = channel();
+
+    std::thread::spawn(move || {
+        for reply in replies {
+            let Ok((mut stream, _)) = listener.accept() else {
+                return;
+            };
+
+            let request = read_request(&mut stream);
+            let _ = tx.send(String::from_utf8_lossy(&request).to_lowercase());
+
+            if reply.hang {
+                std::thread::sleep(HANG_FOR);
+                continue;
+            }
+
+            let _ = stream.write_all(&reply.to_bytes());
+        }
+    });
+
+    Server { url, requests }
+}
+
+/// Reads a request head and as much of the body as it declares.
+fn read_request(stream: &mut std::net::TcpStream) -> Vec<u8> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 4096];
+
+    let head_end = loop {
+        if let Some(i) = request.windows(4).position(|x| x == b"\r\n\r\n") {
+            break i + 4;
+        }
+
+        match stream.read(&mut buf) {
+            Ok(0) | Err(_) => return request,
+            Ok(n) => request.extend_from_slice(&buf[..n]),
+        }
+    };
+
+    let head = String::from_utf8_lossy(&request[..head_end]).to_lowercase();
+    let length = head
+        .lines()
+        .find_map(|x| x.strip_prefix("content-length:"))
+        .and_then(|x| x.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    while request.len() < head_end + length {
+        match stream.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => request.extend_from_slice(&buf[..n]),
+        }
+    }
+
+    request
+}
@@ -0,0 +1,238 @@
+//! Programmatic construction of TII protocols.
+//!
+//! [`ProtocolBuilder`] assembles a [`spec::TiiFile`] piece by piece, for tests
+//! and code generators that produce a protocol without hand-writing the TII
+//! JSON. The result goes through the same [`Protocol`] type as a parsed file.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::core::TirEnvelope;
+use crate::tii::{spec, Error, Protocol};
+
+/// Builder for a [`Protocol`] assembled in code.
+///
+/// # Example
+///
+/// ```ignore
+/// use serde_json::json;
+/// use tx3_sdk::tii::ProtocolBuilder;
+///
+/// let protocol = ProtocolBuilder::new()
+///     .tii_version("v1beta0")
+///     .name("transfer")
+///     .version("0.1.0")
+///     .party("sender", None)
+///     .transaction("transfer", tir, json!({ "type": "object", "properties": {} }))
+///     .build()?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProtocolBuilder {
+    tii_version: Option<String>,
+    name: Option<String>,
+    version: String,
+    scope: String,
+    description: Option<String>,
+    environment: Option<Value>,
+    parties: HashMap<String, spec::Party>,
+    transactions: HashMap<String, spec::Transaction>,
+    tx_order: Vec<String>,
+    profiles: HashMap<String, spec::Profile>,
+    schemas: HashMap<String, Value>,
+    default_profile: Option<String>,
+}
+
+impl ProtocolBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the TII specification version (required).
+    pub fn tii_version(mut self, version: impl Into<String>) -> Self {
+        self.tii_version = Some(version.into());
+        self
+    }
+
+    /// Sets the protocol name (required).
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the protocol version. Defaults to an empty string.
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    /// Sets the protocol scope. Defaults to an empty string.
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = scope.into();
+        self
+    }
+
+    /// Sets the protocol description.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the JSON schema for the protocol environment.
+    pub fn environment(mut self, schema: Value) -> Self {
+        self.environment = Some(schema);
+        self
+    }
+
+    /// Adds a party, replacing any party with the same name.
+    pub fn party(mut self, name: impl Into<String>, description: Option<String>) -> Self {
+        self.parties
+            .insert(name.into(), spec::Party { description });
+        self
+    }
+
+    /// Adds a transaction, replacing any transaction with the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The transaction name
+    /// * `tir` - The transaction's TIR envelope
+    /// * `params` - The JSON schema of the transaction params
+    pub fn transaction(mut self, name: impl Into<String>, tir: TirEnvelope, params: Value) -> Self {
+        let tx = spec::Transaction {
+            tir,
+            params,
+            description: None,
+        };
+
+        let name = name.into();
+
+        if !self.tx_order.contains(&name) {
+            self.tx_order.push(name.clone());
+        }
+
+        self.transactions.insert(name, tx);
+        self
+    }
+
+    /// Adds a profile, replacing any profile with the same name.
+    pub fn profile(mut self, name: impl Into<String>, profile: spec::Profile) -> Self {
+        self.profiles.insert(name.into(), profile);
+        self
+    }
+
+    /// Sets the profile `invoke` falls back to when none is selected.
+    pub fn default_profile(mut self, name: impl Into<String>) -> Self {
+        self.default_profile = Some(name.into());
+        self
+    }
+
+    /// Adds a reusable schema under `components.schemas`, replacing any
+    /// schema with the same name.
+    pub fn component(mut self, name: impl Into<String>, schema: Value) -> Self {
+        self.schemas.insert(name.into(), schema);
+        self
+    }
+
+    /// Validates the required fields and builds the [`Protocol`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MissingField` if the TII version or the protocol name
+    /// were not set, or are empty, and `Error::UnsupportedTiiVersion` if the
+    /// TII version is not one this SDK understands.
+    pub fn build(self) -> Result<Protocol, Error> {
+        let tii_version = self
+            .tii_version
+            .filter(|x| !x.is_empty())
+            .ok_or_else(|| Error::MissingField("tii.version".to_string()))?;
+
+        let name = self
+            .name
+            .filter(|x| !x.is_empty())
+            .ok_or_else(|| Error::MissingField("protocol.name".to_string()))?;
+
+        let components = (!self.schemas.is_empty()).then_some(spec::Components {
+            schemas: self.schemas,
+        });
+
+        let spec = spec::TiiFile {
+            tii: spec::TiiInfo {
+                version: tii_version,
+                default_profile: None,
+            },
+            protocol: spec::Protocol {
+                name,
+                version: self.version,
+                scope: self.scope,
+                description: self.description,
+            },
+            environment: self.environment,
+            parties: self.parties,
+            transactions: self.transactions,
+            profiles: self.profiles,
+            components,
+            default_profile: self.default_profile,
+        };
+
+        Ok(Protocol::from_spec(spec)?.with_tx_order(self.tx_order))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_fixtures::hex_tir;
+    use crate::tii::ParamType;
+
+    #[test]
+    fn builds_an_invocable_protocol() {
+        let protocol = ProtocolBuilder::new()
+            .tii_version("v1beta0")
+            .name("transfer")
+            .version("0.1.0")
+            .environment(json!({
+                "type": "object",
+                "properties": { "tax": { "type": "integer" } }
+            }))
+            .party("sender", None)
+            .transaction(
+                "transfer",
+                hex_tir("00"),
+                json!({
+                    "type": "object",
+                    "properties": { "quantity": { "type": "integer" } }
+                }),
+            )
+            .profile(
+                "local",
+                spec::Profile {
+                    environment: json!({ "tax": 10 }),
+                    ..Default::default()
+                },
+            )
+            .build()
+            .unwrap();
+
+        let mut invoke = protocol.invoke("transfer", Some("local")).unwrap();
+        let params = invoke.params();
+
+        assert!(matches!(params["sender"], ParamType::Address));
+        assert!(matches!(params["tax"], ParamType::Integer));
+        assert!(matches!(params["quantity"], ParamType::Integer));
+    }
+
+    #[test]
+    fn requires_tii_version_and_name() {
+        let err = ProtocolBuilder::new().name("transfer").build().unwrap_err();
+        assert!(matches!(err, Error::MissingField(field) if field == "tii.version"));
+
+        let err = ProtocolBuilder::new()
+            .tii_version("v1beta0")
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, Error::MissingField(field) if field == "protocol.name"));
+    }
+}
@@ -0,0 +1,172 @@
+//! Generic CBOR to JSON decoding.
+//!
+//! Used wherever the SDK receives CBOR it needs to look into without typed
+//! structs for it: TIR payloads, CBOR-encoded arg bundles and CBOR responses
+//! from TRP gateways.
+
+use pallas_codec::minicbor::{data::Type, Decoder};
+use serde_json::{Map, Value};
+
+/// Bound on CBOR nesting, so a hostile payload can't overflow the stack.
+const MAX_DEPTH: usize = 256;
+
+/// Decodes a single CBOR item into JSON.
+///
+/// Byte strings become hex strings, integers outside the `i64` range become
+/// decimal strings and non-string map keys become their JSON text. Bignums
+/// (tags 2 and 3) up to 128 bits decode like any other integer; every other
+/// tag is kept as `{"tag": <number>, "value": <item>}` so constructor tags of
+/// Plutus data aren't lost. Everything else maps to the natural JSON value.
+///
+/// The input must hold exactly one item: trailing bytes are an error rather
+/// than silently ignored.
+pub(crate) fn to_json(bytes: &[u8]) -> Result<Value, String> {
+    let mut decoder = Decoder::new(bytes);
+    let value = cbor_to_json(&mut decoder, 0)?;
+
+    if decoder.position() != bytes.len() {
+        return Err(format!(
+            "{} trailing bytes after CBOR item",
+            bytes.len() - decoder.position()
+        ));
+    }
+
+    Ok(value)
+}
+
+fn cbor_to_json(d: &mut Decoder<'_>, depth: usize) -> Result<Value, String> {
+    if depth > MAX_DEPTH {
+        return Err(format!("nested deeper than {MAX_DEPTH} levels"));
+    }
+
+    let err = |e: pallas_codec::minicbor::decode::Error| e.to_string();
+
+    let value = match d.datatype().map_err(err)? {
+        Type::Bool => Value::Bool(d.bool().map_err(err)?),
+        Type::Null => {
+            d.null().map_err(err)?;
+            Value::Null
+        }
+        Type::Undefined => {
+            d.undefined().map_err(err)?;
+            Value::Null
+        }
+        Type::U8 | Type::U16 | Type::U32 | Type::U64 => Value::from(d.u64().map_err(err)?),
+        Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::Int => {
+            let int = i128::from(d.int().map_err(err)?);
+
+            match i64::try_from(int) {
+                Ok(x) => Value::from(x),
+                Err(_) => Value::String(int.to_string()),
+            }
+        }
+        Type::F16 => Value::from(d.f16().map_err(err)?),
+        Type::F32 => Value::from(d.f32().map_err(err)?),
+        Type::F64 => Value::from(d.f64().map_err(err)?),
+        Type::Simple => Value::from(d.simple().map_err(err)?),
+        Type::Bytes => Value::String(hex::encode(d.bytes().map_err(err)?)),
+        Type::BytesIndef => {
+            let mut out = Vec::new();
+            for chunk in d.bytes_iter().map_err(err)? {
+                out.extend_from_slice(chunk.map_err(err)?);
+            }
+            Value::String(hex::encode(out))
+        }
+        Type::String => Value::String(d.str().map_err(err)?.to_string()),
+        Type::StringIndef => {
+            let mut out = String::new();
+            for chunk in d.str_iter().map_err(err)? {
+                out.push_str(chunk.map_err(err)?);
+            }
+            Value::String(out)
+        }
+        Type::Array | Type::ArrayIndef => {
+            let len = d.array().map_err(err)?;
+            let mut out = Vec::new();
+
+            while more(d, len, out.len())? {
+                out.push(cbor_to_json(d, depth + 1)?);
+            }
+
+            Value::Array(out)
+        }
+        Type::Map | Type::MapIndef => {
+            let len = d.map().map_err(err)?;
+            let mut out = Map::new();
+            let mut count = 0;
+
+            while more(d, len, count)? {
+                let key = match cbor_to_json(d, depth + 1)? {
+                    Value::String(x) => x,
+                    other => other.to_string(),
+                };
+
+                out.insert(key, cbor_to_json(d, depth + 1)?);
+                count += 1;
+            }
+
+            Value::Object(out)
+        }
+        Type::Tag => {
+            let tag = d.tag().map_err(err)?.as_u64();
+            let value = cbor_to_json(d, depth + 1)?;
+
+            match bignum(tag, &value) {
+                Some(int) => int,
+                None => serde_json::json!({ "tag": tag, "value": value }),
+            }
+        }
+        other => return Err(format!("unexpected CBOR item: {other}")),
+    };
+
+    Ok(value)
+}
+
+/// Decodes a positive (tag 2) or negative (tag 3) bignum whose magnitude
+/// fits in 128 bits, given its byte string already decoded to hex.
+fn bignum(tag: u64, value: &Value) -> Option<Value> {
+    if tag != 2 && tag != 3 {
+        return None;
+    }
+
+    let bytes = hex::decode(value.as_str()?).ok()?;
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    let digits = &bytes[start..];
+
+    if digits.len() > 16 {
+        return None;
+    }
+
+    let n = digits.iter().fold(0u128, |n, b| (n << 8) | u128::from(*b));
+
+    let value = if tag == 2 {
+        match u64::try_from(n) {
+            Ok(x) => Value::from(x),
+            Err(_) => Value::String(n.to_string()),
+        }
+    } else {
+        // a tag 3 bignum encodes -1 - n
+        match i64::try_from(n).ok().and_then(|x| (-1i64).checked_sub(x)) {
+            Some(x) => Value::from(x),
+            None => Value::String(format!("-{}", n.checked_add(1)?)),
+        }
+    };
+
+    Some(value)
+}
+
+/// Whether a definite (`len`) or indefinite container has another item,
+/// consuming the terminating break of an indefinite one.
+fn more(d: &mut Decoder<'_>, len: Option<u64>, seen: usize) -> Result<bool, String> {
+    match len {
+        Some(len) => Ok((seen as u64) < len),
+        None => {
+            if d.datatype().map_err(|e| e.to_string())? == Type::Break {
+                d.set_position(d.position() + 1);
+                Ok(false)
+            } else {
+                Ok(true)
+            }
+        }
+    }
+}
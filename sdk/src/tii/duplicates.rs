@@ -0,0 +1,148 @@
+//! Detection of duplicate transaction and profile keys in raw TII JSON.
+//!
+//! JSON objects with repeated keys deserialize last-wins, so a TII with two
+//! `transfer` transactions (common when files are generated by concatenation)
+//! would silently lose one. This pre-pass walks the raw text before the real
+//! parse and only records the keys of the sections where a collision matters.
+//! The same pass yields the declaration order of transactions, which a parsed
+//! map no longer has.
+
+use serde::de::{self, Deserializer, IgnoredAny, MapAccess, Visitor};
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::tii::Error;
+
+/// Sections checked for duplicate keys.
+const SECTIONS: [&str; 2] = ["transactions", "profiles"];
+
+/// Fails with `Error::DuplicateKey` if a checked section repeats a key, and
+/// otherwise returns the transaction names in document order.
+///
+/// Malformed JSON is not reported here; the regular parse that follows
+/// produces the proper error for it.
+pub(super) fn check(code: &str) -> Result<Vec<String>, Error> {
+    let Ok(Sections(sections)) = serde_json::from_str::<Sections>(code) else {
+        return Ok(Vec::new());
+    };
+
+    for (section, keys) in sections.iter() {
+        let mut seen = HashSet::new();
+
+        for key in keys {
+            if !seen.insert(key) {
+                return Err(Error::DuplicateKey {
+                    section: section.to_string(),
+                    key: key.clone(),
+                });
+            }
+        }
+    }
+
+    let txs = sections
+        .into_iter()
+        .find(|(section, _)| *section == "transactions")
+        .map(|(_, keys)| keys)
+        .unwrap_or_default();
+
+    Ok(txs)
+}
+
+/// Keys of each checked section, in document order and including repeats.
+struct Sections(Vec<(&'static str, Vec<String>)>);
+
+impl<'de> de::Deserialize<'de> for Sections {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(SectionsVisitor)
+    }
+}
+
+struct SectionsVisitor;
+
+impl<'de> Visitor<'de> for SectionsVisitor {
+    type Value = Sections;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a TII object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Sections, A::Error> {
+        let mut out = Vec::new();
+
+        while let Some(key) = map.next_key::<String>()? {
+            match SECTIONS.iter().find(|x| **x == key) {
+                Some(section) => out.push((*section, map.next_value::<Keys>()?.0)),
+                None => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(Sections(out))
+    }
+}
+
+/// The keys of a JSON object, values skipped.
+struct Keys(Vec<String>);
+
+impl<'de> de::Deserialize<'de> for Keys {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(KeysVisitor)
+    }
+}
+
+struct KeysVisitor;
+
+impl<'de> Visitor<'de> for KeysVisitor {
+    type Value = Keys;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Keys, A::Error> {
+        let mut keys = Vec::new();
+
+        while let Some(key) = map.next_key::<String>()? {
+            map.next_value::<IgnoredAny>()?;
+            keys.push(key);
+        }
+
+        Ok(Keys(keys))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_repeated_keys_in_checked_sections() {
+        let code = r#"{
+            "transactions": { "transfer": {}, "mint": {}, "transfer": {} },
+            "profiles": { "local": {} }
+        }"#;
+
+        match check(code) {
+            Err(Error::DuplicateKey { section, key }) => {
+                assert_eq!(section, "transactions");
+                assert_eq!(key, "transfer");
+            }
+            other => panic!("expected duplicate key, got {other:?}"),
+        }
+
+        let code = r#"{ "profiles": { "local": {}, "local": { "environment": {} } } }"#;
+        assert!(matches!(check(code), Err(Error::DuplicateKey { .. })));
+    }
+
+    #[test]
+    fn ignores_other_sections_and_malformed_input() {
+        let code = r#"{
+            "parties": { "sender": {}, "sender": {} },
+            "transactions": { "transfer": { "params": { "a": 1, "a": 2 } } }
+        }"#;
+
+        assert_eq!(check(code).unwrap(), vec!["transfer"]);
+        assert!(check("{ not json").unwrap().is_empty());
+    }
+}
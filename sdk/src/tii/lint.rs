@@ -0,0 +1,343 @@
+//! Authoring checks over the schemas embedded in a TII.
+//!
+//! Parsing only guarantees the TII has the right structure; the params and
+//! environment schemas are free-form JSON. [`lint`] walks them looking for
+//! mistakes that would otherwise surface as untyped params or resolver
+//! errors at runtime: invalid `type` keywords, dangling `$ref`s, `required`
+//! entries without a matching property, unknown `x-address-kind`s.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::tii::{AddressKind, ParamType, Protocol};
+
+/// The JSON Schema primitive type names.
+const SCHEMA_TYPES: [&str; 7] = [
+    "null", "boolean", "object", "array", "number", "string", "integer",
+];
+
+/// Keywords whose value is a single subschema.
+const SUBSCHEMA_KEYWORDS: [&str; 3] = ["items", "additionalProperties", "not"];
+
+/// Keywords whose value is a list of subschemas.
+const SUBSCHEMA_LIST_KEYWORDS: [&str; 4] = ["prefixItems", "oneOf", "anyOf", "allOf"];
+
+/// How serious a [`LintFinding`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintSeverity {
+    /// Likely unintended, but the protocol still works.
+    Warning,
+    /// The schema is broken; affected params can't be typed or resolved.
+    Error,
+}
+
+/// A problem found by [`Protocol::lint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    /// How serious the problem is.
+    pub severity: LintSeverity,
+    /// Dotted path to the offending node, e.g.
+    /// `transactions.transfer.params.properties.quantity`.
+    pub path: String,
+    /// What is wrong.
+    pub message: String,
+}
+
+/// Lints every params, environment and component schema of a protocol.
+pub(super) fn lint(protocol: &Protocol) -> Vec<LintFinding> {
+    let spec = &protocol.spec;
+
+    let components = spec
+        .components
+        .as_ref()
+        .map(|c| c.schemas.clone())
+        .unwrap_or_default();
+
+    let mut linter = Linter {
+        protocol,
+        components: &components,
+        findings: Vec::new(),
+    };
+
+    if let Some(env) = &spec.environment {
+        linter.root(env, "environment");
+    }
+
+    for (name, tx) in protocol.ordered_txs() {
+        linter.root(&tx.params, &format!("transactions.{name}.params"));
+    }
+
+    let mut names: Vec<_> = components.keys().collect();
+    names.sort();
+
+    for name in names {
+        linter.node(&components[name], &format!("components.schemas.{name}"), 0);
+    }
+
+    linter.findings
+}
+
+struct Linter<'a> {
+    protocol: &'a Protocol,
+    components: &'a HashMap<String, Value>,
+    findings: Vec<LintFinding>,
+}
+
+impl Linter<'_> {
+    fn report(&mut self, severity: LintSeverity, path: &str, message: impl Into<String>) {
+        self.findings.push(LintFinding {
+            severity,
+            path: path.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Lints a params or environment schema, which must describe an object
+    /// whose properties become params.
+    fn root(&mut self, schema: &Value, path: &str) {
+        let schema = self.protocol.deref_schema(schema);
+
+        if schema.get("$ref").is_none()
+            && schema.get("type").and_then(Value::as_str) != Some("object")
+        {
+            self.report(
+                LintSeverity::Warning,
+                path,
+                "expected `\"type\": \"object\"`, its properties are the params",
+            );
+        }
+
+        self.node(schema, path, 0);
+
+        let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+            return;
+        };
+
+        for (key, property) in properties {
+            if let ParamType::Unknown(_) = ParamType::from_json_schema(property, self.components) {
+                self.report(
+                    LintSeverity::Warning,
+                    &format!("{path}.properties.{key}"),
+                    "schema is not a shape the SDK can type; the param is passed through untyped",
+                );
+            }
+        }
+    }
+
+    fn node(&mut self, schema: &Value, path: &str, depth: usize) {
+        if depth > self.protocol.max_schema_depth {
+            self.report(
+                LintSeverity::Error,
+                path,
+                format!(
+                    "nested deeper than {} levels",
+                    self.protocol.max_schema_depth
+                ),
+            );
+            return;
+        }
+
+        let Some(obj) = schema.as_object() else {
+            if !schema.is_boolean() {
+                self.report(LintSeverity::Error, path, "schema must be an object");
+            }
+            return;
+        };
+
+        if let Some(ty) = obj.get("type") {
+            self.check_type(ty, path);
+        }
+
+        if let Some(reference) = obj.get("$ref") {
+            self.check_ref(reference, path);
+        }
+
+        if let Some(kind) = obj.get("x-address-kind") {
+            if kind.as_str().and_then(AddressKind::from_name).is_none() {
+                self.report(
+                    LintSeverity::Error,
+                    path,
+                    format!("unknown `x-address-kind` {kind}"),
+                );
+            }
+        }
+
+        match obj.get("properties") {
+            Some(Value::Object(properties)) => {
+                for (key, property) in properties {
+                    self.node(property, &format!("{path}.properties.{key}"), depth + 1);
+                }
+            }
+            Some(_) => self.report(LintSeverity::Error, path, "`properties` must be an object"),
+            None => (),
+        }
+
+        if let Some(required) = obj.get("required") {
+            self.check_required(required, obj.get("properties"), path);
+        }
+
+        for keyword in SUBSCHEMA_KEYWORDS {
+            if let Some(sub) = obj.get(keyword) {
+                self.node(sub, &format!("{path}.{keyword}"), depth + 1);
+            }
+        }
+
+        for keyword in SUBSCHEMA_LIST_KEYWORDS {
+            match obj.get(keyword) {
+                Some(Value::Array(items)) => {
+                    for (i, sub) in items.iter().enumerate() {
+                        self.node(sub, &format!("{path}.{keyword}.{i}"), depth + 1);
+                    }
+                }
+                Some(_) => self.report(
+                    LintSeverity::Error,
+                    path,
+                    format!("`{keyword}` must be an array"),
+                ),
+                None => (),
+            }
+        }
+    }
+
+    fn check_type(&mut self, ty: &Value, path: &str) {
+        let names: Vec<_> = match ty {
+            Value::String(x) => vec![x.as_str()],
+            Value::Array(items) => items.iter().filter_map(Value::as_str).collect(),
+            _ => {
+                self.report(
+                    LintSeverity::Error,
+                    path,
+                    "`type` must be a string or an array",
+                );
+                return;
+            }
+        };
+
+        for name in names {
+            if !SCHEMA_TYPES.contains(&name) {
+                self.report(LintSeverity::Error, path, format!("unknown type `{name}`"));
+            }
+        }
+    }
+
+    fn check_ref(&mut self, reference: &Value, path: &str) {
+        let Some(reference) = reference.as_str() else {
+            self.report(LintSeverity::Error, path, "`$ref` must be a string");
+            return;
+        };
+
+        if reference.starts_with('#') {
+            if self.protocol.resolve_ref(reference).is_none() {
+                self.report(
+                    LintSeverity::Error,
+                    path,
+                    format!("`$ref` `{reference}` points at no schema in `components.schemas`"),
+                );
+            }
+        } else if ParamType::core_ref_type(reference).is_none() {
+            self.report(
+                LintSeverity::Warning,
+                path,
+                format!("`$ref` `{reference}` is neither a core type nor a local component"),
+            );
+        }
+    }
+
+    fn check_required(&mut self, required: &Value, properties: Option<&Value>, path: &str) {
+        let Some(required) = required.as_array() else {
+            self.report(LintSeverity::Error, path, "`required` must be an array");
+            return;
+        };
+
+        let properties = properties.and_then(Value::as_object);
+
+        for key in required {
+            let Some(key) = key.as_str() else {
+                self.report(
+                    LintSeverity::Error,
+                    path,
+                    "`required` entries must be strings",
+                );
+                continue;
+            };
+
+            if properties.is_some_and(|x| !x.contains_key(key)) {
+                self.report(
+                    LintSeverity::Warning,
+                    path,
+                    format!("`{key}` is required but not declared in `properties`"),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_fixtures::protocol_with_params;
+
+    fn protocol(params: Value) -> Protocol {
+        protocol_with_params(params)
+            .component("Point", json!({ "type": "object", "properties": {} }))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn fixtures_are_clean() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+        assert_eq!(protocol.lint(), vec![]);
+
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/complex.tii")).unwrap();
+        let errors: Vec<_> = protocol
+            .lint()
+            .into_iter()
+            .filter(|x| x.severity == LintSeverity::Error)
+            .collect();
+        assert_eq!(errors, vec![]);
+    }
+
+    #[test]
+    fn reports_broken_schemas_with_paths() {
+        let findings = protocol(json!({
+            "type": "object",
+            "properties": {
+                "count": { "type": "int" },
+                "point": { "$ref": "#/components/schemas/Point" },
+                "missing": { "$ref": "#/components/schemas/Nope" },
+                "owner": { "type": "string", "x-address-kind": "multisig" }
+            },
+            "required": ["count", "ghost"]
+        }))
+        .lint();
+
+        let find = |path: &str, severity| {
+            findings
+                .iter()
+                .any(|x| x.path == path && x.severity == severity)
+        };
+
+        let base = "transactions.tx.params";
+        assert!(find(
+            &format!("{base}.properties.count"),
+            LintSeverity::Error
+        ));
+        assert!(find(
+            &format!("{base}.properties.missing"),
+            LintSeverity::Error
+        ));
+        assert!(find(
+            &format!("{base}.properties.owner"),
+            LintSeverity::Error
+        ));
+        assert!(find(base, LintSeverity::Warning));
+        assert!(!findings
+            .iter()
+            .any(|x| x.path == format!("{base}.properties.point")));
+    }
+}
@@ -30,6 +30,9 @@
 //!
 //! // Or load from JSON value
 //! let protocol = Protocol::from_json(json_value)?;
+//!
+//! // Or fetch from a URL
+//! let protocol = Protocol::from_url("https://example.com/protocol.tii", DEFAULT_MAX_TII_BYTES).await?;
 //! ```
 //!
 //! ### Invoking a Transaction
@@ -64,34 +67,82 @@
 //! for different networks. When invoking a transaction with a profile, those values are
 //! automatically populated.
 
+use base64::Engine as _;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
 use thiserror::Error;
 
 use crate::{
-    core::{ArgMap, TirEnvelope},
-    tii::spec::{Profile, Transaction},
+    core::{ArgMap, AssetArg, TirEnvelope, UtxoRef},
+    tii::spec::{Network, Profile, Transaction},
+    trp::{ResolvePoint, SelectionStrategy},
 };
 
+mod builder;
+pub(crate) mod cbor;
+mod duplicates;
+mod lint;
 mod schema;
 pub mod spec;
+pub(crate) mod tir;
+mod wallet;
+
+pub use builder::ProtocolBuilder;
+pub use lint::{LintFinding, LintSeverity};
+pub use schema::{
+    AddressKind, ParamConstraints, ParamMap, ParamType, VariantCase, DEFAULT_MAX_SCHEMA_DEPTH,
+};
+pub use tir::{InputQuery, TirParam};
+pub use wallet::Wallet;
+
+/// TII file-format versions this SDK can interpret.
+pub const SUPPORTED_TII_VERSIONS: &[&str] = &["v1beta0"];
 
-pub use schema::{ParamMap, ParamType, VariantCase};
+/// A byte limit for [`Protocol::from_reader`] that fits any hand-written or
+/// generated TII, including ones embedding large scripts (16 MiB).
+pub const DEFAULT_MAX_TII_BYTES: usize = 16 * 1024 * 1024;
 
 /// Error type for TII operations.
 ///
 /// This enum represents all possible errors that can occur when loading
 /// and interacting with TX3 protocol definitions.
-#[derive(Debug, Error)]
+///
+/// Errors are `Clone`, so they can be stored (e.g. in a retry queue); the
+/// underlying library errors are shared behind an `Arc`.
+#[derive(Debug, Clone, Error)]
 pub enum Error {
     /// Invalid JSON in the TII file.
     #[error("invalid TII JSON: {0}")]
-    InvalidJson(#[from] serde_json::Error),
+    InvalidJson(#[source] Arc<serde_json::Error>),
+
+    /// Invalid YAML in the TII file.
+    #[cfg(feature = "yaml")]
+    #[error("invalid TII YAML: {0}")]
+    InvalidYaml(#[source] Arc<serde_yaml::Error>),
 
     /// Failed to read the TII file from disk.
     #[error("failed to read file: {0}")]
-    IoError(#[from] std::io::Error),
+    IoError(#[source] Arc<std::io::Error>),
+
+    /// Failed to fetch the TII file from a remote URL.
+    #[error("failed to fetch TII: {0}")]
+    FetchError(#[source] Arc<reqwest::Error>),
+
+    /// The remote server answered with a non-success HTTP status.
+    #[error("failed to fetch TII: HTTP {0} {1}")]
+    HttpError(u16, String),
+
+    /// The remote server answered with an HTML page instead of a TII document.
+    #[error("failed to fetch TII: expected a JSON document, got content type `{0}`")]
+    UnexpectedContentType(String),
+
+    /// The TII declares a file-format version this SDK doesn't understand.
+    ///
+    /// See [`SUPPORTED_TII_VERSIONS`].
+    #[error("unsupported TII version `{0}`, supported: {supported}", supported = SUPPORTED_TII_VERSIONS.join(", "))]
+    UnsupportedTiiVersion(String),
 
     /// Transaction name not found in the protocol.
     #[error("unknown tx: {0}")]
@@ -100,6 +151,148 @@ pub enum Error {
     /// Profile name not found in the protocol.
     #[error("unknown profile: {0}")]
     UnknownProfile(String),
+
+    /// The TII contains a field the spec doesn't define (strict parsing only).
+    ///
+    /// Carries the dotted path of the offending field, e.g. `transaction`
+    /// or `profiles.preprod.enviroment`.
+    #[error("unknown field in TII: {0}")]
+    UnknownField(String),
+
+    /// A params or environment schema nests deeper than the configured limit.
+    ///
+    /// Cyclic component refs end up here too. See
+    /// [`Protocol::max_schema_depth`].
+    #[error("schema nested deeper than {0} levels")]
+    SchemaTooDeep(usize),
+
+    /// An asset arg has an invalid name or policy.
+    #[error("invalid asset for arg `{key}`: {source}")]
+    InvalidAssetName {
+        key: String,
+        #[source]
+        source: crate::core::AssetError,
+    },
+
+    /// A section of the TII declares the same key twice.
+    ///
+    /// Only detected when parsing from text; a `serde_json::Value` has
+    /// already collapsed repeated keys.
+    #[error("duplicate key `{key}` in TII `{section}`")]
+    DuplicateKey { section: String, key: String },
+
+    /// The TIR payload of a tx could not be decoded.
+    #[error("invalid TIR: {0}")]
+    InvalidTir(String),
+
+    /// An argument value can't be used for its param.
+    #[error("invalid value for arg `{key}`: {reason}")]
+    InvalidArg { key: String, reason: String },
+
+    /// A CBOR arg bundle could not be decoded into an arg map.
+    #[error("invalid CBOR args: {0}")]
+    InvalidCborArgs(String),
+
+    /// The profile supplies no value for any param the tx reads.
+    #[error("profile `{profile}` supplies no args used by tx `{tx}`")]
+    IrrelevantProfile { tx: String, profile: String },
+
+    /// A party name passed to [`Invocation::with_parties`] is not declared
+    /// by the protocol.
+    #[error("unknown party: {0}")]
+    UnknownParty(String),
+
+    /// A param declared in a tx's schema is never read by its TIR.
+    #[error("param `{param}` of tx `{tx}` is declared but not read by its TIR")]
+    UnusedParam { tx: String, param: String },
+
+    /// A tx's TIR reads a param the TII doesn't declare.
+    #[error("TIR of tx `{tx}` reads param `{param}`, which the TII doesn't declare")]
+    UndeclaredParam { tx: String, param: String },
+
+    /// A tx's TIR and its params schema disagree on how many params the tx
+    /// takes, parties and environment aside.
+    #[error("TIR of tx `{tx}` expects {tir} params but its schema declares {schema}")]
+    ParamCountMismatch {
+        tx: String,
+        tir: usize,
+        schema: usize,
+    },
+
+    /// Metadata passed to [`Invocation::with_metadata`] can't be encoded as
+    /// transaction metadata.
+    #[error("invalid metadata for label {label}: {reason}")]
+    InvalidMetadata { label: u64, reason: String },
+
+    /// A required field was not provided when building a protocol, or is
+    /// empty in a loaded TII.
+    #[error("missing required field: {0}")]
+    MissingField(String),
+
+    /// A link passed to [`Protocol::from_link`] is not a valid invocation link.
+    #[error("invalid invocation link: {0}")]
+    InvalidLink(String),
+
+    /// A TII read by [`Protocol::from_reader`], [`Protocol::from_async_reader`]
+    /// or [`Protocol::from_url`] is larger than the limit.
+    #[error("TII is larger than the limit of {limit} bytes")]
+    FileTooLarge { limit: usize },
+
+    /// A loaded TII declares no transactions.
+    #[error("TII declares no transactions")]
+    MissingTransactions,
+
+    /// A profile sets a value for a key that matches no declared param.
+    ///
+    /// Only raised when strict profile checks are enabled, see
+    /// [`Protocol::strict_profiles`].
+    #[error("profile `{profile}` sets `{key}`, which is not a param of the tx")]
+    UnknownProfileArg { profile: String, key: String },
+
+    /// An `Address` argument isn't a valid bech32, base58 or hex address.
+    #[error("arg `{key}` is not a valid address")]
+    InvalidAddress { key: String },
+
+    /// An `Address` argument isn't of the kind its schema requires.
+    #[error("address arg `{key}` must be a {expected} address")]
+    InvalidAddressKind { key: String, expected: AddressKind },
+
+    /// A param schema's `x-address-kind` names no known [`AddressKind`].
+    #[error("param `{key}` declares an unknown x-address-kind {kind}")]
+    UnknownAddressKind { key: String, kind: String },
+
+    /// An address argument belongs to a different network than the profile.
+    #[error("address arg `{key}` is for {got}, but the profile targets {expected}")]
+    NetworkMismatch {
+        key: String,
+        expected: String,
+        got: String,
+    },
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Self::InvalidJson(Arc::new(error))
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl From<serde_yaml::Error> for Error {
+    fn from(error: serde_yaml::Error) -> Self {
+        Self::InvalidYaml(Arc::new(error))
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self::IoError(Arc::new(error))
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        Self::FetchError(Arc::new(error))
+    }
 }
 
 /// A TX3 protocol loaded from a TII file.
@@ -125,9 +318,75 @@ pub enum Error {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Protocol {
     spec: spec::TiiFile,
+
+    #[serde(skip)]
+    strict_profiles: bool,
+
+    #[serde(skip, default = "default_max_schema_depth")]
+    max_schema_depth: usize,
+
+    #[serde(skip)]
+    case_policy: CasePolicy,
+
+    /// Transaction names in declaration order, when known.
+    #[serde(skip)]
+    tx_order: Vec<String>,
+}
+
+fn default_max_schema_depth() -> usize {
+    schema::DEFAULT_MAX_SCHEMA_DEPTH
 }
 
 impl Protocol {
+    fn from_spec(spec: spec::TiiFile) -> Result<Protocol, Error> {
+        let version = &spec.tii.version;
+
+        if !SUPPORTED_TII_VERSIONS.contains(&version.as_str()) {
+            return Err(Error::UnsupportedTiiVersion(version.clone()));
+        }
+
+        let defaults = [&spec.default_profile, &spec.tii.default_profile];
+
+        if let Some(missing) = defaults
+            .into_iter()
+            .flatten()
+            .find(|x| !spec.profiles.contains_key(*x))
+        {
+            return Err(Error::UnknownProfile(missing.clone()));
+        }
+
+        Ok(Protocol {
+            spec,
+            strict_profiles: false,
+            max_schema_depth: schema::DEFAULT_MAX_SCHEMA_DEPTH,
+            case_policy: CasePolicy::default(),
+            tx_order: Vec::new(),
+        })
+    }
+
+    /// Like `from_spec`, first rejecting a TII that parses but is
+    /// structurally empty, which is almost always a loading mistake.
+    fn from_loaded_spec(spec: spec::TiiFile) -> Result<Protocol, Error> {
+        if spec.tii.version.trim().is_empty() {
+            return Err(Error::MissingField("tii.version".to_string()));
+        }
+
+        if spec.protocol.name.trim().is_empty() {
+            return Err(Error::MissingField("protocol.name".to_string()));
+        }
+
+        if spec.transactions.is_empty() {
+            return Err(Error::MissingTransactions);
+        }
+
+        Protocol::from_spec(spec)
+    }
+
+    fn with_tx_order(mut self, order: Vec<String>) -> Self {
+        self.tx_order = order;
+        self
+    }
+
     /// Creates a Protocol from a JSON value.
     ///
     /// # Arguments
@@ -138,6 +397,12 @@ impl Protocol {
     ///
     /// Returns a `Protocol` on success, or an error if the JSON is invalid.
     ///
+    /// # Errors
+    ///
+    /// Besides parse errors, returns `Error::MissingField` if `tii.version`
+    /// or `protocol.name` is empty and `Error::MissingTransactions` if the TII
+    /// declares no transactions.
+    ///
     /// # Example
     ///
     /// ```ignore
@@ -145,9 +410,9 @@ impl Protocol {
     /// use serde_json::json;
     ///
     /// let json = json!({
-    ///     "tii": { "version": "1.0.0" },
+    ///     "tii": { "version": "v1beta0" },
     ///     "protocol": { "name": "MyProtocol", "version": "1.0.0" },
-    ///     "transactions": {}
+    ///     "transactions": { /* at least one tx */ }
     /// });
     ///
     /// let protocol = Protocol::from_json(json)?;
@@ -155,7 +420,41 @@ impl Protocol {
     pub fn from_json(json: serde_json::Value) -> Result<Protocol, Error> {
         let spec = serde_json::from_value(json)?;
 
-        Ok(Protocol { spec })
+        Protocol::from_loaded_spec(spec)
+    }
+
+    /// Creates a Protocol from a JSON value, rejecting unknown fields.
+    ///
+    /// Works like [`Protocol::from_json`], except that any field the TII
+    /// spec doesn't define fails the parse instead of being ignored, so a
+    /// typo like `transaction` for `transactions` is caught upfront. Free-form
+    /// sections (params and environment schemas, profile environments) are
+    /// not checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnknownField` with the path of the first unknown field,
+    /// or `Error::InvalidJson` if the value doesn't match the spec otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use tx3_sdk::tii::Protocol;
+    ///
+    /// let protocol = Protocol::from_json_strict(json_value)?;
+    /// ```
+    pub fn from_json_strict(json: serde_json::Value) -> Result<Protocol, Error> {
+        let mut unknown = None;
+
+        let spec = serde_ignored::deserialize(json, |path| {
+            unknown.get_or_insert_with(|| path.to_string());
+        })?;
+
+        if let Some(path) = unknown {
+            return Err(Error::UnknownField(path));
+        }
+
+        Protocol::from_loaded_spec(spec)
     }
 
     /// Creates a Protocol from a JSON string.
@@ -166,7 +465,8 @@ impl Protocol {
     ///
     /// # Returns
     ///
-    /// Returns a `Protocol` on success, or an error if the JSON is invalid.
+    /// Returns a `Protocol` on success, or an error if the JSON is invalid or
+    /// a transaction or profile name is declared twice.
     ///
     /// # Example
     ///
@@ -174,16 +474,77 @@ impl Protocol {
     /// use tx3_sdk::tii::Protocol;
     ///
     /// let tii_content = r#"{
-    ///     "tii": { "version": "1.0.0" },
+    ///     "tii": { "version": "v1beta0" },
     ///     "protocol": { "name": "MyProtocol", "version": "1.0.0" },
-    ///     "transactions": {}
+    ///     "transactions": { /* at least one tx */ }
     /// }"#;
     ///
     /// let protocol = Protocol::from_string(tii_content.to_string())?;
     /// ```
     pub fn from_string(code: String) -> Result<Protocol, Error> {
+        let order = duplicates::check(&code)?;
+
         let json = serde_json::from_str(&code)?;
-        Self::from_json(json)
+        Ok(Self::from_json(json)?.with_tx_order(order))
+    }
+
+    /// Creates a Protocol from a YAML string.
+    ///
+    /// The YAML document has the same structure as a JSON TII, so authors can
+    /// keep a commented YAML source without a separate conversion step.
+    ///
+    /// Requires the `yaml` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - A string containing the TII YAML content
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Protocol` on success, or an error if the YAML is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use tx3_sdk::tii::Protocol;
+    ///
+    /// let protocol = Protocol::from_yaml(&std::fs::read_to_string("protocol.tii.yaml")?)?;
+    /// ```
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(code: &str) -> Result<Protocol, Error> {
+        let spec = serde_yaml::from_str(code)?;
+
+        Protocol::from_loaded_spec(spec)
+    }
+
+    /// Creates a Protocol from a TII embedded in the binary at compile time.
+    ///
+    /// Intended for tools that ship with a baked-in protocol, typically via
+    /// `include_str!`. The content is parsed straight from the borrowed
+    /// string, without copying it into an owned `String` first.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - A static string containing the TII JSON content
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Protocol` on success, or an error if the JSON is invalid or
+    /// a transaction or profile name is declared twice.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use tx3_sdk::tii::Protocol;
+    ///
+    /// let protocol = Protocol::from_static(include_str!("../protocol.tii"))?;
+    /// ```
+    pub fn from_static(json: &'static str) -> Result<Protocol, Error> {
+        let order = duplicates::check(json)?;
+
+        let spec = serde_json::from_str(json)?;
+
+        Ok(Protocol::from_loaded_spec(spec)?.with_tx_order(order))
     }
 
     /// Creates a Protocol from a file path.
@@ -209,327 +570,3829 @@ impl Protocol {
         Self::from_string(code)
     }
 
-    fn ensure_tx(&self, key: &str) -> Result<&Transaction, Error> {
-        let tx = self.spec.transactions.get(key);
-        let tx = tx.ok_or(Error::UnknownTx(key.to_string()))?;
+    /// Creates a Protocol by reading a TII file from a reader, refusing input
+    /// larger than `max_bytes`.
+    ///
+    /// At most `max_bytes + 1` bytes are read, so an oversized upload is
+    /// rejected before it is buffered in full or parsed. Servers accepting
+    /// user-supplied TIIs should load them through this;
+    /// [`DEFAULT_MAX_TII_BYTES`] is a sensible limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::FileTooLarge` if the input exceeds `max_bytes`,
+    /// `Error::IoError` if reading fails or the input isn't UTF-8, plus any
+    /// error `from_string` returns.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use tx3_sdk::tii::{Protocol, DEFAULT_MAX_TII_BYTES};
+    ///
+    /// let protocol = Protocol::from_reader(request_body, DEFAULT_MAX_TII_BYTES)?;
+    /// ```
+    pub fn from_reader(reader: impl std::io::Read, max_bytes: usize) -> Result<Protocol, Error> {
+        use std::io::Read as _;
 
-        Ok(tx)
+        // read bytes, not a string: the cut at `max_bytes + 1` may split a
+        // UTF-8 sequence, which must still report the size, not the encoding
+        let mut code = Vec::new();
+        let limit = (max_bytes as u64).saturating_add(1);
+        reader.take(limit).read_to_end(&mut code)?;
+
+        Self::from_capped_bytes(code, max_bytes)
     }
 
-    fn ensure_profile(&self, key: &str) -> Result<&Profile, Error> {
-        let env = self
-            .spec
-            .profiles
-            .get(key)
-            .ok_or_else(|| Error::UnknownProfile(key.to_string()))?;
+    /// Creates a Protocol by reading a TII file from an async reader, failing
+    /// if it's larger than `max_bytes`.
+    ///
+    /// The async counterpart of [`Protocol::from_reader`], for async servers
+    /// loading TIIs from object storage or a socket without blocking the
+    /// runtime on a sync read. At most `max_bytes + 1` bytes are buffered.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::FileTooLarge` if the input exceeds `max_bytes`,
+    /// `Error::IoError` if reading fails or the input isn't UTF-8, plus any
+    /// error `from_string` returns.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use tx3_sdk::tii::{Protocol, DEFAULT_MAX_TII_BYTES};
+    ///
+    /// let file = tokio::fs::File::open("./my_protocol.tii").await?;
+    /// let protocol = Protocol::from_async_reader(file, DEFAULT_MAX_TII_BYTES).await?;
+    /// ```
+    pub async fn from_async_reader(
+        reader: impl tokio::io::AsyncRead + Unpin,
+        max_bytes: usize,
+    ) -> Result<Protocol, Error> {
+        use tokio::io::AsyncReadExt as _;
 
-        Ok(env)
+        let mut code = Vec::new();
+        let limit = (max_bytes as u64).saturating_add(1);
+        reader.take(limit).read_to_end(&mut code).await?;
+
+        Self::from_capped_bytes(code, max_bytes)
     }
 
-    /// Creates an invocation for a transaction.
-    ///
-    /// This method initializes an invocation for the specified transaction,
-    /// optionally applying a profile to pre-populate arguments.
+    /// Parses a TII read into memory, checking it against `max_bytes`.
+    fn from_capped_bytes(code: Vec<u8>, max_bytes: usize) -> Result<Protocol, Error> {
+        if code.len() > max_bytes {
+            return Err(Error::FileTooLarge { limit: max_bytes });
+        }
+
+        let code = String::from_utf8(code)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        Self::from_string(code)
+    }
+
+    /// Creates a Protocol by fetching a TII file from a URL.
     ///
     /// # Arguments
     ///
-    /// * `tx` - The name of the transaction to invoke
-    /// * `profile` - Optional profile name to apply (e.g., "mainnet", "preview")
+    /// * `url` - The URL of the remote TII file
+    /// * `max_bytes` - Size limit for the body, see [`DEFAULT_MAX_TII_BYTES`]
     ///
     /// # Returns
     ///
-    /// Returns an `Invocation` that can be configured with arguments and
-    /// converted to a TRP resolve request.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if:
-    /// - The transaction name is not found
-    /// - The profile name is not found (if specified)
+    /// Returns a `Protocol` on success, or an error if the request fails, the
+    /// server answers with a non-2xx status or a non-JSON body, or the JSON
+    /// is invalid. A body announcing or streaming more than `max_bytes` fails
+    /// with `Error::FileTooLarge` before it is buffered in full.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// use tx3_sdk::tii::Protocol;
-    ///
-    /// let protocol = Protocol::from_file("protocol.tii")?;
+    /// use tx3_sdk::tii::{Protocol, DEFAULT_MAX_TII_BYTES};
     ///
-    /// // Invoke with a profile
-    /// let invocation = protocol.invoke("transfer", Some("mainnet"))?;
-    ///
-    /// // Invoke without a profile
-    /// let invocation = protocol.invoke("transfer", None)?;
+    /// let protocol =
+    ///     Protocol::from_url("https://example.com/transfer.tii.json", DEFAULT_MAX_TII_BYTES).await?;
     /// ```
-    pub fn invoke(&self, tx: &str, profile: Option<&str>) -> Result<Invocation, Error> {
-        let tx = self.ensure_tx(tx)?;
+    pub async fn from_url(url: &str, max_bytes: usize) -> Result<Protocol, Error> {
+        let response = reqwest::get(url).await?;
 
-        let profile = profile.map(|x| self.ensure_profile(x)).transpose()?;
+        let status = response.status();
 
-        let mut out = Invocation {
-            tir: tx.tir.clone(),
-            params: ParamMap::new(),
-            args: ArgMap::new(),
-        };
+        if !status.is_success() {
+            return Err(Error::HttpError(status.as_u16(), status.to_string()));
+        }
 
-        let components: HashMap<String, Value> = self
-            .spec
-            .components
-            .as_ref()
-            .map(|c| c.schemas.clone())
-            .unwrap_or_default();
+        // Raw file hosts often serve JSON as `text/plain`, so only HTML is
+        // rejected upfront (login pages, 404 pages behind a 200, etc). Any
+        // other body is left for the JSON parser to judge.
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|x| x.to_str().ok())
+            .map(str::to_string);
 
-        for party in self.spec.parties.keys() {
-            out.params.insert(party.to_lowercase(), ParamType::Address);
+        if let Some(content_type) = content_type {
+            if content_type.to_lowercase().contains("html") {
+                return Err(Error::UnexpectedContentType(content_type));
+            }
         }
 
-        if let Some(env) = &self.spec.environment {
-            out.params.extend(schema::params_from_schema(env, &components));
+        let too_large = Error::FileTooLarge { limit: max_bytes };
+
+        if response
+            .content_length()
+            .is_some_and(|x| x > max_bytes as u64)
+        {
+            return Err(too_large);
         }
 
-        out.params.extend(schema::params_from_schema(&tx.params, &components));
+        let mut response = response;
+        let mut code = Vec::new();
 
-        if let Some(profile) = profile {
-            if let Some(env) = profile.environment.as_object() {
-                let values = env.clone();
-                out.set_args(values);
+        while let Some(chunk) = response.chunk().await? {
+            if code.len() + chunk.len() > max_bytes {
+                return Err(too_large);
             }
 
-            for (key, value) in profile.parties.iter() {
-                out.set_arg(key, json!(value));
-            }
+            code.extend_from_slice(&chunk);
         }
 
-        Ok(out)
+        Self::from_capped_bytes(code, max_bytes)
     }
 
-    /// Returns all transactions defined in the protocol.
-    ///
-    /// # Returns
-    ///
-    /// Returns a reference to the map of transaction names to their definitions.
-    pub fn txs(&self) -> &HashMap<String, spec::Transaction> {
-        &self.spec.transactions
+    /// Starts a [`ProtocolBuilder`] to assemble a protocol in code.
+    pub fn builder() -> ProtocolBuilder {
+        ProtocolBuilder::new()
     }
 
-    /// Returns all parties defined in the protocol.
+    /// Enables or disables strict profile checks.
     ///
-    /// # Returns
+    /// By default, profile environment and party values are merged into the
+    /// invocation args even when no param matches them. With strict checks
+    /// on, `invoke` and `Invocation::reapply_profile` fail with
+    /// `Error::UnknownProfileArg` instead, which catches profiles that drift
+    /// out of sync with the transaction's params.
     ///
-    /// Returns a reference to the map of party names to their definitions.
-    pub fn parties(&self) -> &HashMap<String, spec::Party> {
-        &self.spec.parties
+    /// # Example
+    ///
+    /// ```ignore
+    /// use tx3_sdk::tii::Protocol;
+    ///
+    /// let protocol = Protocol::from_file("protocol.tii")?.strict_profiles(true);
+    /// ```
+    pub fn strict_profiles(mut self, strict: bool) -> Self {
+        self.strict_profiles = strict;
+        self
     }
 
-    /// Returns all profiles defined in the protocol.
-    pub fn profiles(&self) -> &HashMap<String, spec::Profile> {
-        &self.spec.profiles
+    /// Sets how deep params and environment schemas may nest.
+    ///
+    /// Defaults to [`DEFAULT_MAX_SCHEMA_DEPTH`]. `invoke` fails with
+    /// `Error::SchemaTooDeep` on schemas nesting deeper than this, which
+    /// bounds the recursion when handling untrusted TII files.
+    pub fn max_schema_depth(mut self, depth: usize) -> Self {
+        self.max_schema_depth = depth;
+        self
     }
 
-    /// Starts a [`Tx3ClientBuilder`] for this protocol. Configure TRP options,
-    /// optional profile selection, party bindings, and env overrides, then
-    /// call `build()` to obtain a [`crate::Tx3Client`].
-    pub fn client(self) -> crate::facade::Tx3ClientBuilder {
-        crate::facade::Tx3ClientBuilder::from_protocol(self)
+    /// Sets how the invocations of this protocol spell arg names given to
+    /// `Invocation::set_arg`.
+    ///
+    /// Defaults to [`CasePolicy::Lowercase`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use tx3_sdk::tii::{CasePolicy, Protocol};
+    ///
+    /// let protocol = Protocol::from_file("protocol.tii")?.case_policy(CasePolicy::MatchDeclared);
+    /// ```
+    pub fn case_policy(mut self, policy: CasePolicy) -> Self {
+        self.case_policy = policy;
+        self
     }
-}
 
-/// Input query specification.
-///
-/// This type is currently a placeholder for future input query functionality.
-pub struct InputQuery {}
+    fn check_profile_args(
+        &self,
+        name: &str,
+        profile: &Profile,
+        params: &ParamMap,
+    ) -> Result<(), Error> {
+        if !self.strict_profiles {
+            return Ok(());
+        }
 
-/// Map of input queries.
-///
-/// Used to represent input queries for transaction resolution.
-pub type QueryMap = BTreeMap<String, InputQuery>;
+        let env_keys = profile.environment.as_object().into_iter().flatten();
+        let env_keys = env_keys.map(|(key, _)| key.clone());
+        let party_keys = profile.parties.keys().map(|key| key.to_lowercase());
 
-/// An active transaction invocation.
-///
-/// This structure represents a transaction that is being prepared for execution.
-/// It holds the transaction template (TIR), parameter definitions, and current
-/// argument values.
-///
-/// Use the builder methods (`with_arg`, `with_args`) to populate arguments,
-/// then convert to a TRP resolve request using `into_resolve_request`.
-///
-/// # Example
-///
-/// ```ignore
-/// use serde_json::json;
-/// use tx3_sdk::tii::Protocol;
-///
-/// let protocol = Protocol::from_file("protocol.tii")?;
-/// let invocation = protocol.invoke("transfer", None)?;
-///
-/// // Set arguments
-/// let invocation = invocation
-///     .with_arg("sender", json!("addr1..."))
-///     .with_arg("amount", json!(1000000));
-///
-/// // Check what's missing
-/// for (name, ty) in invocation.unspecified_params() {
-///     println!("Need: {} ({:?})", name, ty);
-/// }
-///
-/// // Convert to resolve request
-/// let resolve_params = invocation.into_resolve_request()?;
-/// ```
-#[derive(Debug, Clone)]
-pub struct Invocation {
-    tir: TirEnvelope,
-    params: ParamMap,
-    args: ArgMap,
-    // TODO: support explicit input specification
-    // input_override: HashMap<String, v1beta0::UtxoSet>,
+        for key in env_keys.chain(party_keys) {
+            if !params.contains_key(&key) {
+                return Err(Error::UnknownProfileArg {
+                    profile: name.to_string(),
+                    key,
+                });
+            }
+        }
 
-    // TODO: support explicit fee specification
-    // fee_override: Option<u64>,
-}
+        Ok(())
+    }
 
-impl Invocation {
-    /// Returns a reference to all parameters for this invocation.
+    /// Checks the protocol's schemas for authoring mistakes.
     ///
-    /// # Returns
+    /// Walks every params, environment and component schema and reports
+    /// invalid `type` keywords, `$ref`s that point nowhere, `required`
+    /// entries without a property, unknown `x-address-kind`s, and params the
+    /// SDK can't type. Meant as a
+    /// developer-time check before shipping a TII; an empty result means no
+    /// problems were found.
     ///
-    /// A reference to the map of parameter names to their types.
-    pub fn params(&mut self) -> &ParamMap {
-        &self.params
+    /// # Example
+    ///
+    /// ```ignore
+    /// for finding in protocol.lint() {
+    ///     eprintln!("{:?} {}: {}", finding.severity, finding.path, finding.message);
+    /// }
+    /// ```
+    pub fn lint(&self) -> Vec<LintFinding> {
+        lint::lint(self)
     }
 
-    /// Returns an iterator over parameters that haven't been specified yet.
-    ///
-    /// This is useful for checking which required arguments are still missing
-    /// before submitting the transaction.
+    /// Looks up a `#/components/schemas/<Name>` reference in the protocol's
+    /// components section.
     ///
     /// # Returns
     ///
-    /// An iterator over (name, type) pairs for unspecified parameters.
-    pub fn unspecified_params(&mut self) -> impl Iterator<Item = (&String, &ParamType)> {
-        self.params
-            .iter()
-            .filter(|(k, _)| !self.args.contains_key(k.as_str()))
+    /// Returns the referenced schema, or `None` if the reference is not in
+    /// that form or names a schema that doesn't exist.
+    pub fn resolve_ref(&self, reference: &str) -> Option<&Value> {
+        let name = reference.strip_prefix("#/components/schemas/")?;
+
+        self.spec.components.as_ref()?.schemas.get(name)
     }
 
-    /// Sets a single argument value.
+    /// Returns a Blake2b-256 hash of the protocol's canonical JSON form.
     ///
-    /// # Arguments
+    /// The spec is serialized with every object's keys sorted and no
+    /// whitespace, so two TIIs that differ only in formatting or key order
+    /// hash the same. Loader settings such as strict profiles are not part of
+    /// the hash.
     ///
-    /// * `name` - The parameter name (case-insensitive)
-    /// * `value` - The JSON value to set
-    pub fn set_arg(&mut self, name: &str, value: serde_json::Value) {
-        self.args.insert(name.to_lowercase().to_string(), value);
+    /// # Example
+    ///
+    /// ```ignore
+    /// if deployed.canonical_hash() != local.canonical_hash() {
+    ///     println!("the deployed protocol differs from the local copy");
+    /// }
+    /// ```
+    pub fn canonical_hash(&self) -> [u8; 32] {
+        let spec = serde_json::to_value(&self.spec).unwrap_or_default();
+        let bytes = serde_json::to_vec(&canonical_json(spec)).unwrap_or_default();
+
+        *pallas_crypto::hash::Hasher::<256>::hash(&bytes)
     }
 
-    /// Sets multiple argument values at once.
+    /// Returns the `title` of a component schema, for use as a UI heading.
     ///
-    /// # Arguments
-    ///
-    /// * `args` - A map of argument names to values
-    pub fn set_args(&mut self, args: ArgMap) {
-        self.args.extend(args);
+    /// `ref_name` is either a `#/components/schemas/<Name>` reference or the
+    /// bare component name. A component that is itself a `$ref` to another one
+    /// without a title of its own takes the title of the component it aliases.
+    pub fn schema_title(&self, ref_name: &str) -> Option<&str> {
+        self.component_label(ref_name, "title")
     }
 
-    /// Sets a single argument value (builder pattern).
+    /// Returns the `description` of a component schema, looked up as in
+    /// [`Protocol::schema_title`].
+    pub fn schema_description(&self, ref_name: &str) -> Option<&str> {
+        self.component_label(ref_name, "description")
+    }
+
+    fn component_label(&self, ref_name: &str, keyword: &str) -> Option<&str> {
+        let name = ref_name
+            .strip_prefix("#/components/schemas/")
+            .unwrap_or(ref_name);
+
+        let schema = self.spec.components.as_ref()?.schemas.get(name)?;
+
+        schema
+            .get(keyword)
+            .or_else(|| self.deref_schema(schema).get(keyword))
+            .and_then(Value::as_str)
+    }
+
+    /// Follows a chain of top-level component `$ref`s, so an environment or
+    /// params schema declared as a reference exposes the referenced
+    /// `properties`. Stops at the first node that isn't a resolvable ref, or
+    /// when the chain loops back on itself.
+    fn deref_schema<'a>(&'a self, schema: &'a Value) -> &'a Value {
+        let mut current = schema;
+        let mut seen = HashSet::new();
+
+        while let Some(reference) = current.get("$ref").and_then(Value::as_str) {
+            if !seen.insert(reference) {
+                break;
+            }
+
+            match self.resolve_ref(reference) {
+                Some(resolved) => current = resolved,
+                None => break,
+            }
+        }
+
+        current
+    }
+
+    fn ensure_tx(&self, key: &str) -> Result<&Transaction, Error> {
+        let tx = self.spec.transactions.get(key);
+        let tx = tx.ok_or(Error::UnknownTx(key.to_string()))?;
+
+        Ok(tx)
+    }
+
+    fn ensure_profile(&self, key: &str) -> Result<&Profile, Error> {
+        let env = self
+            .spec
+            .profiles
+            .get(key)
+            .ok_or_else(|| Error::UnknownProfile(key.to_string()))?;
+
+        Ok(env)
+    }
+
+    /// Creates an invocation for a transaction.
     ///
-    /// This is the builder-pattern variant of `set_arg`, allowing chained calls.
+    /// This method initializes an invocation for the specified transaction,
+    /// optionally applying a profile to pre-populate arguments.
     ///
     /// # Arguments
     ///
-    /// * `name` - The parameter name (case-insensitive)
-    /// * `value` - The JSON value to set
+    /// * `tx` - The name of the transaction to invoke
+    /// * `profile` - Optional profile name to apply (e.g., "mainnet", "preview").
+    ///   When `None`, the protocol's [`Protocol::default_profile`] is applied,
+    ///   if it declares one; use [`Protocol::invoke_without_profile`] to apply
+    ///   none.
+    ///
+    /// A profile's environment values fill any param with the same name,
+    /// whether it is declared in the environment schema or only in the tx's
+    /// own params, so a TII without an `environment` schema can still ship
+    /// per-network values for tx params. Values matching no param are kept
+    /// as extra args (see [`Invocation::arg_report`]), or rejected under
+    /// [`Protocol::strict_profiles`].
     ///
     /// # Returns
     ///
-    /// Returns `self` for method chaining.
-    pub fn with_arg(mut self, name: &str, value: serde_json::Value) -> Self {
-        self.args.insert(name.to_lowercase().to_string(), value);
-        self
-    }
-
-    /// Sets multiple argument values at once (builder pattern).
+    /// Returns an `Invocation` that can be configured with arguments and
+    /// converted to a TRP resolve request.
     ///
-    /// This is the builder-pattern variant of `set_args`, allowing chained calls.
+    /// # Errors
     ///
-    /// # Arguments
+    /// Returns an error if:
+    /// - The transaction name is not found
+    /// - The profile name is not found (if specified)
+    /// - Strict profile checks are on and the profile sets a value matching
+    ///   no param
+    /// - A param schema declares an unknown `x-address-kind`
+    ///   (`Error::UnknownAddressKind`)
     ///
-    /// * `args` - A map of argument names to values
+    /// # Example
     ///
-    /// # Returns
+    /// ```ignore
+    /// use tx3_sdk::tii::Protocol;
     ///
-    /// Returns `self` for method chaining.
-    pub fn with_args(mut self, args: ArgMap) -> Self {
-        self.args.extend(args);
-        self
+    /// let protocol = Protocol::from_file("protocol.tii")?;
+    ///
+    /// // Invoke with a profile
+    /// let invocation = protocol.invoke("transfer", Some("mainnet"))?;
+    ///
+    /// // Invoke with the default profile, if the TII declares one
+    /// let invocation = protocol.invoke("transfer", None)?;
+    /// ```
+    pub fn invoke(&self, tx: &str, profile: Option<&str>) -> Result<Invocation, Error> {
+        let profile = profile
+            .or_else(|| self.default_profile())
+            .map(|x| self.ensure_profile(x).map(|p| (x, p)))
+            .transpose()?;
+
+        self.build_invocation(tx, profile)
     }
 
-    /// Converts this invocation into a TRP resolve request.
+    /// Creates an invocation with no profile applied, not even the
+    /// [`Protocol::default_profile`].
     ///
-    /// This method consumes the invocation and creates the parameters needed
-    /// to call the TRP `resolve` method.
+    /// Params are filled from schema defaults only.
     ///
-    /// # Returns
+    /// # Errors
     ///
-    /// Returns `ResolveParams` that can be passed to `trp::Client::resolve`.
+    /// The errors [`Protocol::invoke`] returns, other than the profile ones.
+    pub fn invoke_without_profile(&self, tx: &str) -> Result<Invocation, Error> {
+        self.build_invocation(tx, None)
+    }
+
+    /// Builds an invocation of `tx`, applying `profile` if given.
+    fn build_invocation(
+        &self,
+        tx: &str,
+        profile: Option<(&str, &Profile)>,
+    ) -> Result<Invocation, Error> {
+        let name = tx;
+        let tx = self.ensure_tx(tx)?;
+
+        let mut out = Invocation {
+            tx: name.to_string(),
+            tir: tx.tir.clone(),
+            params: ParamMap::new(),
+            args: ArgMap::new(),
+            explicit_args: HashSet::new(),
+            defaults: ArgMap::new(),
+            constraints: HashMap::new(),
+            examples: HashMap::new(),
+            extra: serde_json::Map::new(),
+            selection_strategy: SelectionStrategy::default(),
+            at: None,
+            network: None,
+            profile: None,
+            parties: HashSet::new(),
+            case_policy: self.case_policy,
+            metadata: BTreeMap::new(),
+            fee_input: None,
+        };
+
+        let components: HashMap<String, Value> = self
+            .spec
+            .components
+            .as_ref()
+            .map(|c| c.schemas.clone())
+            .unwrap_or_default();
+
+        for party in self.spec.parties.keys() {
+            out.params.insert(party.to_lowercase(), ParamType::Address);
+            out.parties.insert(party.to_lowercase());
+        }
+
+        if let Some(env) = &self.spec.environment {
+            let env = self.deref_schema(env);
+            let depth = self.max_schema_depth;
+            out.params
+                .extend(schema::params_from_schema(env, &components, depth)?);
+            out.defaults.extend(schema::defaults_from_schema(env));
+            out.constraints
+                .extend(schema::constraints_from_schema(env)?);
+            out.examples.extend(schema::examples_from_schema(env));
+        }
+
+        let tx_params = self.deref_schema(&tx.params);
+        out.params.extend(schema::params_from_schema(
+            tx_params,
+            &components,
+            self.max_schema_depth,
+        )?);
+        out.defaults.extend(schema::defaults_from_schema(tx_params));
+        out.constraints
+            .extend(schema::constraints_from_schema(tx_params)?);
+        out.examples.extend(schema::examples_from_schema(tx_params));
+
+        out.args.extend(out.defaults.clone());
+
+        if let Some((name, profile)) = profile {
+            self.check_profile_args(name, profile, &out.params)?;
+            out.apply_profile(name, profile);
+        }
+
+        Ok(out)
+    }
+
+    /// Checks that a profile is meaningful for a transaction.
+    ///
+    /// A profile is considered relevant when at least one of its environment
+    /// values or parties is a param the tx reads. Which params a tx reads is
+    /// taken from its TIR; if the TIR can't be decoded, every declared param
+    /// counts. Use it to catch a wrong profile selection before invoking.
     ///
     /// # Errors
     ///
-    /// Currently this method always succeeds, but returns `Result` for future
-    /// compatibility.
-    pub fn into_resolve_request(self) -> Result<crate::trp::ResolveParams, Error> {
-        let args = self.args.clone().into_iter().collect();
+    /// `Error::UnknownTx` or `Error::UnknownProfile` if either doesn't exist,
+    /// and `Error::IrrelevantProfile` if the profile supplies nothing the tx
+    /// uses.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// protocol.can_invoke("transfer", "preprod")?;
+    /// let invocation = protocol.invoke("transfer", Some("preprod"))?;
+    /// ```
+    pub fn can_invoke(&self, tx: &str, profile: &str) -> Result<(), Error> {
+        let transaction = self.ensure_tx(tx)?;
+        let selected = self.ensure_profile(profile)?;
 
-        let tir = self.tir.clone();
+        let used = match tir::decode(&transaction.tir) {
+            Ok(ir) => tir::referenced_params(&ir),
+            Err(_) => self
+                .build_invocation(tx, None)?
+                .params
+                .into_keys()
+                .collect(),
+        };
+
+        let env = selected.environment.as_object().into_iter().flatten();
+        let mut supplied = env.map(|(k, _)| k).chain(selected.parties.keys());
 
-        Ok(crate::trp::ResolveParams {
-            tir,
-            args,
-            // We're already merging env into params / args, no need to send it independently.
-            // Having both mechanism is a footgun. We should revisit either the TRP schema to
-            // remove the option or split how we send the env in the SDK.
-            env: None,
+        if supplied.any(|k| used.contains(&k.to_lowercase())) {
+            return Ok(());
+        }
+
+        Err(Error::IrrelevantProfile {
+            tx: tx.to_string(),
+            profile: profile.to_string(),
         })
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::collections::HashSet;
+    /// Cross-checks every tx's declared params against its compiled TIR.
+    ///
+    /// A param declared in the tx's `params` schema must be read by the IR,
+    /// as a value or as an input; every value the IR reads must be declared,
+    /// by the tx, the environment or as a party; and the IR must read as many
+    /// values, parties and environment aside, as the tx declares params.
+    /// Names compare ignoring case. Catches a TII and IR that drifted apart
+    /// before the mismatch surfaces as a resolve failure.
+    ///
+    /// # Errors
+    ///
+    /// Every mismatch found, as `Error::UnusedParam`,
+    /// `Error::UndeclaredParam` or `Error::ParamCountMismatch`, plus
+    /// `Error::InvalidTir` for any tx whose TIR can't be decoded.
+    pub fn check_tir_params(&self) -> Result<(), Vec<Error>> {
+        let mut errors = Vec::new();
 
-    use serde_json::json;
+        for (name, tx) in self.ordered_txs() {
+            let ir = match tir::decode(&tx.tir) {
+                Ok(ir) => ir,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
 
-    use super::*;
+            let declared: HashSet<_> = match self.build_invocation(name, None) {
+                Ok(invocation) => invocation.params.keys().map(|x| x.to_lowercase()).collect(),
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
 
-    #[test]
-    fn happy_path_smoke_test() {
-        let manifest_dir = env!("CARGO_MANIFEST_DIR");
-        let tii = format!("{manifest_dir}/tests/fixtures/transfer.tii");
+            let values = tir::referenced_params(&ir);
+            let inputs: HashSet<_> = tir::input_queries(&ir)
+                .into_iter()
+                .map(|x| x.name.to_lowercase())
+                .collect();
 
-        let protocol = Protocol::from_file(&tii).unwrap();
+            let own = self
+                .deref_schema(&tx.params)
+                .get("properties")
+                .and_then(Value::as_object)
+                .into_iter()
+                .flat_map(|x| x.keys())
+                .map(|x| x.to_lowercase())
+                .collect::<std::collections::BTreeSet<_>>();
 
-        let invoke = protocol.invoke("transfer", Some("preprod")).unwrap();
+            for param in &own {
+                if !values.contains(param) && !inputs.contains(param) {
+                    errors.push(Error::UnusedParam {
+                        tx: name.to_string(),
+                        param: param.clone(),
+                    });
+                }
+            }
 
-        let mut invoke = invoke
-            .with_arg("sender", json!("addr1abc"))
-            .with_arg("quantity", json!(100_000_000));
+            let mut undeclared: Vec<_> = values
+                .iter()
+                .filter(|x| !declared.contains(*x))
+                .cloned()
+                .collect();
+            undeclared.sort();
 
-        let all_params: HashSet<_> = invoke.params().keys().collect();
+            errors.extend(undeclared.into_iter().map(|param| Error::UndeclaredParam {
+                tx: name.to_string(),
+                param,
+            }));
 
-        assert_eq!(all_params.len(), 5);
-        assert!(all_params.contains(&"sender".to_string()));
-        assert!(all_params.contains(&"middleman".to_string()));
-        assert!(all_params.contains(&"receiver".to_string()));
-        assert!(all_params.contains(&"tax".to_string()));
-        assert!(all_params.contains(&"quantity".to_string()));
+            // parties and environment params are shared by every tx, so they
+            // count on neither side unless the tx declares them itself
+            let tir = values
+                .iter()
+                .filter(|x| own.contains(*x) || !declared.contains(*x))
+                .count();
 
-        let unspecified_params: HashSet<_> = invoke.unspecified_params().map(|(k, _)| k).collect();
+            if tir != own.len() {
+                errors.push(Error::ParamCountMismatch {
+                    tx: name.to_string(),
+                    tir,
+                    schema: own.len(),
+                });
+            }
+        }
 
-        assert_eq!(unspecified_params.len(), 2);
-        assert!(unspecified_params.contains(&"middleman".to_string()));
-        assert!(unspecified_params.contains(&"receiver".to_string()));
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
 
-        let tx = invoke.into_resolve_request().unwrap();
+    /// Opens a link made by [`Invocation::to_link`].
+    ///
+    /// Invokes the linked tx with the linked profile, then sets the linked
+    /// args as explicit args.
+    ///
+    /// # Errors
+    ///
+    /// `Error::InvalidLink` if the link can't be decoded, plus any error
+    /// `invoke` returns for the tx and profile it names.
+    pub fn from_link(&self, link: &str) -> Result<Invocation, Error> {
+        #[derive(Deserialize)]
+        struct Link {
+            tx: String,
+            profile: Option<String>,
+            #[serde(default)]
+            args: ArgMap,
+        }
 
-        dbg!(&tx);
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(link.trim())
+            .map_err(|e| Error::InvalidLink(e.to_string()))?;
+
+        let link: Link =
+            serde_json::from_slice(&bytes).map_err(|e| Error::InvalidLink(e.to_string()))?;
+
+        let mut out = match &link.profile {
+            Some(profile) => self.invoke(&link.tx, Some(profile))?,
+            None => self.invoke_without_profile(&link.tx)?,
+        };
+        out.set_args(link.args);
+
+        Ok(out)
+    }
+
+    /// Returns the TII file-format version the protocol was written in.
+    pub fn tii_version(&self) -> &str {
+        &self.spec.tii.version
+    }
+
+    /// Returns the transactions in the order the protocol declares them.
+    ///
+    /// Protocol authors usually order transactions logically, which `txs()`
+    /// loses. The declaration order is known for protocols parsed from text
+    /// (`from_string`, `from_file`, `from_static`, `from_url`) and built with
+    /// [`ProtocolBuilder`]; otherwise transactions are listed by name.
+    pub fn ordered_txs(&self) -> Vec<(&str, &spec::Transaction)> {
+        let txs = &self.spec.transactions;
+
+        let mut out: Vec<_> = self
+            .tx_order
+            .iter()
+            .filter_map(|name| txs.get_key_value(name))
+            .map(|(name, tx)| (name.as_str(), tx))
+            .collect();
+
+        let mut rest: Vec<_> = txs
+            .iter()
+            .filter(|(name, _)| !self.tx_order.contains(name))
+            .map(|(name, tx)| (name.as_str(), tx))
+            .collect();
+
+        rest.sort_by_key(|(name, _)| *name);
+        out.extend(rest);
+        out
+    }
+
+    /// Returns the transaction at position `i` of [`Protocol::ordered_txs`].
+    ///
+    /// Indices are stable for a given protocol, so a menu can list the txs
+    /// and map a numeric selection back to one; pass the name to
+    /// [`Protocol::invoke`] to invoke it. `None` if `i` is out of range.
+    pub fn tx_by_index(&self, i: usize) -> Option<(&str, &spec::Transaction)> {
+        self.ordered_txs().get(i).copied()
+    }
+
+    /// Returns the transactions that can be invoked with the given args.
+    ///
+    /// A tx qualifies when the args, together with the defaults its schema
+    /// declares, cover every one of its params. No profile is applied, so
+    /// values a profile would provide must be included in `args`. Arg names
+    /// are matched case-insensitively and txs are listed in declaration order.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let args = ArgMap::from([("sender".to_string(), json!("addr1..."))]);
+    ///
+    /// for tx in protocol.candidate_txs(&args) {
+    ///     println!("can invoke {tx}");
+    /// }
+    /// ```
+    pub fn candidate_txs(&self, args: &ArgMap) -> Vec<String> {
+        self.ordered_txs()
+            .into_iter()
+            .filter(|(name, _)| {
+                let Ok(mut invocation) = self.build_invocation(name, None) else {
+                    return false;
+                };
+
+                for (key, value) in args {
+                    invocation.set_arg(key, value.clone());
+                }
+
+                let complete = invocation.unspecified_params().next().is_none();
+                complete
+            })
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    /// Returns all transactions defined in the protocol.
+    ///
+    /// The map is unordered; use [`Protocol::ordered_txs`] to list them as
+    /// declared.
+    ///
+    /// # Returns
+    ///
+    /// Returns a reference to the map of transaction names to their definitions.
+    pub fn txs(&self) -> &HashMap<String, spec::Transaction> {
+        &self.spec.transactions
+    }
+
+    /// Returns the TIR version carried by a transaction.
+    ///
+    /// Transactions of the same protocol may carry TIR of different versions
+    /// during a migration, so compatibility can be checked per tx before
+    /// resolving.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnknownTx` if the transaction is not found.
+    pub fn tx_tir_version(&self, tx: &str) -> Result<&str, Error> {
+        let tx = self.ensure_tx(tx)?;
+
+        Ok(&tx.tir.version)
+    }
+
+    /// Returns all parties defined in the protocol.
+    ///
+    /// # Returns
+    ///
+    /// Returns a reference to the map of party names to their definitions.
+    pub fn parties(&self) -> &HashMap<String, spec::Party> {
+        &self.spec.parties
+    }
+
+    /// Returns the description of a party, if the party exists and has one.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The party name as declared in the TII
+    pub fn party_description(&self, name: &str) -> Option<&str> {
+        self.spec.parties.get(name)?.description.as_deref()
+    }
+
+    /// Returns all profiles defined in the protocol.
+    pub fn profiles(&self) -> &HashMap<String, spec::Profile> {
+        &self.spec.profiles
+    }
+
+    /// Returns the profile the TII marks as default, if any.
+    ///
+    /// Read from the top-level `default_profile`, falling back to the one
+    /// under `tii`. It is what [`Protocol::invoke`] applies when no profile
+    /// is selected. A TII whose default names no profile fails to load with
+    /// `Error::UnknownProfile`.
+    pub fn default_profile(&self) -> Option<&str> {
+        self.spec
+            .default_profile
+            .as_deref()
+            .or(self.spec.tii.default_profile.as_deref())
+    }
+
+    /// Returns the network a profile targets, if it declares one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownProfile`] if no profile has that name.
+    pub fn profile_network(&self, name: &str) -> Result<Option<Network>, Error> {
+        Ok(self.ensure_profile(name)?.network.clone())
+    }
+
+    /// Starts a [`Tx3ClientBuilder`] for this protocol. Configure TRP options,
+    /// optional profile selection, party bindings, and env overrides, then
+    /// call `build()` to obtain a [`crate::Tx3Client`].
+    pub fn client(self) -> crate::facade::Tx3ClientBuilder {
+        crate::facade::Tx3ClientBuilder::from_protocol(self)
+    }
+}
+
+/// Protocols are equal when their specs are, compared structurally in the
+/// same canonical form [`Protocol::canonical_hash`] hashes.
+///
+/// Key order and formatting don't matter, array order does. Loader settings
+/// such as strict profiles, and the declaration order of transactions, are
+/// not compared.
+impl PartialEq for Protocol {
+    fn eq(&self, other: &Self) -> bool {
+        let canonical = |x: &Protocol| serde_json::to_value(&x.spec).ok().map(canonical_json);
+
+        canonical(self) == canonical(other)
+    }
+}
+
+impl Eq for Protocol {}
+
+/// Map of input queries.
+///
+/// Used to represent input queries for transaction resolution.
+pub type QueryMap = BTreeMap<String, InputQuery>;
+
+/// A snapshot of how an invocation's args line up with its params, see
+/// [`Invocation::arg_report`].
+///
+/// Every list is sorted by name.
+#[derive(Debug, Clone, Default)]
+pub struct ArgReport {
+    /// Params that have a value, with the value.
+    pub satisfied: Vec<(String, Value)>,
+
+    /// Params still missing a value, with their type.
+    pub missing: Vec<(String, ParamType)>,
+
+    /// Args that match no param.
+    pub extra: Vec<String>,
+}
+
+impl ArgReport {
+    /// Whether every param has a value.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// How `Invocation::set_arg` spells the arg names it is given.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CasePolicy {
+    /// Lowercases every name, so names are matched case-insensitively
+    /// against lowercase params.
+    #[default]
+    Lowercase,
+    /// Keeps names exactly as given.
+    Preserve,
+    /// Spells a name like the param it matches case-insensitively, keeping
+    /// names that match no param as given. For TIRs expecting the exact
+    /// casing a TII declares.
+    MatchDeclared,
+}
+
+/// An active transaction invocation.
+///
+/// This structure represents a transaction that is being prepared for execution.
+/// It holds the transaction template (TIR), parameter definitions, and current
+/// argument values.
+///
+/// Use the builder methods (`with_arg`, `with_args`) to populate arguments,
+/// then convert to a TRP resolve request using `into_resolve_request`.
+///
+/// # Example
+///
+/// ```ignore
+/// use serde_json::json;
+/// use tx3_sdk::tii::Protocol;
+///
+/// let protocol = Protocol::from_file("protocol.tii")?;
+/// let invocation = protocol.invoke("transfer", None)?;
+///
+/// // Set arguments
+/// let invocation = invocation
+///     .with_arg("sender", json!("addr1..."))
+///     .with_arg("amount", json!(1000000));
+///
+/// // Check what's missing
+/// for (name, ty) in invocation.unspecified_params() {
+///     println!("Need: {} ({:?})", name, ty);
+/// }
+///
+/// // Convert to resolve request
+/// let resolve_params = invocation.into_resolve_request()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Invocation {
+    tx: String,
+    tir: TirEnvelope,
+    params: ParamMap,
+    args: ArgMap,
+    explicit_args: HashSet<String>,
+    defaults: ArgMap,
+    constraints: HashMap<String, ParamConstraints>,
+    examples: HashMap<String, Vec<Value>>,
+    extra: serde_json::Map<String, Value>,
+    selection_strategy: SelectionStrategy,
+    at: Option<ResolvePoint>,
+    network: Option<Network>,
+    profile: Option<String>,
+    parties: HashSet<String>,
+    case_policy: CasePolicy,
+    metadata: BTreeMap<u64, Value>,
+    fee_input: Option<UtxoRef>,
+    // TODO: support explicit input specification
+    // input_override: HashMap<String, v1beta0::UtxoSet>,
+
+    // TODO: support explicit fee specification
+    // fee_override: Option<u64>,
+}
+
+impl Invocation {
+    /// Returns a reference to all parameters for this invocation.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the map of parameter names to their types.
+    pub fn params(&mut self) -> &ParamMap {
+        &self.params
+    }
+
+    /// Returns an iterator over parameters that haven't been specified yet.
+    ///
+    /// This is useful for checking which required arguments are still missing
+    /// before submitting the transaction.
+    ///
+    /// # Returns
+    ///
+    /// An iterator over (name, type) pairs for unspecified parameters.
+    pub fn unspecified_params(&mut self) -> impl Iterator<Item = (&String, &ParamType)> {
+        self.params
+            .iter()
+            .filter(|(k, _)| !self.args.contains_key(k.as_str()))
+    }
+
+    /// Reports which params have a value, which are missing one, and which
+    /// args match no param.
+    ///
+    /// Values come from every source merged so far (defaults, profile,
+    /// explicit args), as in `effective_args`. The report is owned, so it can
+    /// be stored or logged alongside the invocation.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let report = invocation.arg_report();
+    ///
+    /// for name in &report.extra {
+    ///     println!("ignored arg: {name}");
+    /// }
+    /// ```
+    pub fn arg_report(&self) -> ArgReport {
+        let mut report = ArgReport::default();
+
+        for (name, ty) in self.params.iter() {
+            match self.args.get(name) {
+                Some(value) => report.satisfied.push((name.clone(), value.clone())),
+                None => report.missing.push((name.clone(), ty.clone())),
+            }
+        }
+
+        report.extra = self
+            .args
+            .keys()
+            .filter(|x| !self.params.contains_key(x.as_str()))
+            .cloned()
+            .collect();
+
+        report.satisfied.sort_by(|(a, _), (b, _)| a.cmp(b));
+        report.missing.sort_by(|(a, _), (b, _)| a.cmp(b));
+        report.extra.sort();
+
+        report
+    }
+
+    /// Lists the args that differ between this invocation and `other`.
+    ///
+    /// Compares the merged args (see `effective_args`) key by key, so a
+    /// difference coming from a profile or a default shows up as well as an
+    /// explicit one. Each entry is `(key, value here, value in other)`, with
+    /// `None` where a side has no value, sorted by key.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for (key, before, after) in last_run.diff_args(&this_run) {
+    ///     println!("{key}: {before:?} -> {after:?}");
+    /// }
+    /// ```
+    pub fn diff_args(&self, other: &Invocation) -> Vec<(String, Option<Value>, Option<Value>)> {
+        let keys: std::collections::BTreeSet<_> =
+            self.args.keys().chain(other.args.keys()).collect();
+
+        keys.into_iter()
+            .filter_map(|key| {
+                let (a, b) = (self.args.get(key), other.args.get(key));
+                (a != b).then(|| (key.clone(), a.cloned(), b.cloned()))
+            })
+            .collect()
+    }
+
+    /// Splits off the params still missing a value, as owned data.
+    ///
+    /// Same as collecting `unspecified_params`, sorted by name, but without
+    /// keeping the invocation borrowed, which suits UIs that store both the
+    /// invocation and the list of remaining params in their state.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let (invocation, missing) = invocation.with_arg("quantity", json!(100)).narrow();
+    ///
+    /// for (name, ty) in missing {
+    ///     println!("still needed: {name} ({ty:?})");
+    /// }
+    /// ```
+    pub fn narrow(mut self) -> (Invocation, Vec<(String, ParamType)>) {
+        let mut missing: Vec<_> = self
+            .unspecified_params()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        missing.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        (self, missing)
+    }
+
+    /// Asks a callback for the value of every param still missing one.
+    ///
+    /// The callback is called once per unspecified param, in name order, and
+    /// any value it returns is set as an explicit arg. Returning `None` leaves
+    /// the param unspecified, to be reported as missing like any other. This
+    /// suits interactive prompts and lookups (e.g. asking a wallet for an
+    /// address) that shouldn't run for params already provided.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// invocation.resolve_missing_with(|name, ty| match ty {
+    ///     ParamType::Address => wallet.address_for(name).map(Value::from),
+    ///     _ => None,
+    /// });
+    /// ```
+    pub fn resolve_missing_with(&mut self, mut f: impl FnMut(&str, &ParamType) -> Option<Value>) {
+        let mut missing: Vec<_> = self
+            .unspecified_params()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        missing.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        for (name, ty) in missing {
+            if let Some(value) = f(&name, &ty) {
+                self.set_arg(&name, value);
+            }
+        }
+    }
+
+    /// Fills missing party and collateral args from a connected wallet.
+    ///
+    /// Every party param still missing an address is set to the wallet's
+    /// [`Wallet::address_for`] that party, if the wallet acts as it, and a
+    /// `collateral` UTxO ref param to its [`Wallet::collateral`]. Args already
+    /// set, explicitly or by a profile, are kept.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut invocation = protocol
+    ///     .invoke("transfer", Some("preprod"))?
+    ///     .with_arg("receiver", json!(receiver));
+    ///
+    /// invocation.bind_wallet(&wallet);
+    /// ```
+    pub fn bind_wallet(&mut self, wallet: &impl Wallet) {
+        let parties = self.parties.clone();
+
+        self.resolve_missing_with(|name, ty| match ty {
+            ParamType::Address if parties.contains(name) => {
+                wallet.address_for(name).map(Value::from)
+            }
+            ParamType::UtxoRef if name == "collateral" => {
+                wallet.collateral().map(|x| Value::from(x.to_string()))
+            }
+            _ => None,
+        });
+    }
+
+    /// Notes parties the applied profile left without an address.
+    ///
+    /// Party params showing up as unspecified after selecting a profile is
+    /// correct, but surprising to users who expect the profile to fill them.
+    /// Each returned message names the profile and a party it doesn't set,
+    /// sorted by party. Empty when no profile is applied or every party has a
+    /// value.
+    pub fn profile_warnings(&self) -> Vec<String> {
+        let Some(profile) = &self.profile else {
+            return Vec::new();
+        };
+
+        let mut parties: Vec<_> = self
+            .parties
+            .iter()
+            .filter(|x| !self.args.contains_key(x.as_str()))
+            .collect();
+
+        parties.sort();
+
+        parties
+            .into_iter()
+            .map(|x| {
+                format!("profile `{profile}` doesn't set party `{x}`, it must be given as an arg")
+            })
+            .collect()
+    }
+
+    /// Returns the value constraints the schema declares for a param.
+    ///
+    /// `None` if the param is unknown or carries no constraints. The bounds
+    /// and lengths are also checked when the resolve request is built; the
+    /// `pattern` is only exposed, for forms to enforce client-side.
+    pub fn param_constraints(&self, name: &str) -> Option<&ParamConstraints> {
+        self.constraints.get(name)
+    }
+
+    /// Returns the example values the schema declares per param.
+    ///
+    /// Read from each property's `examples` list, or its single `example`.
+    /// Params without examples are absent. A "try it" form can pre-fill each
+    /// field with the first example.
+    pub fn param_examples(&self) -> HashMap<String, Vec<Value>> {
+        self.examples.clone()
+    }
+
+    /// Renders a human-readable summary of what this invocation will resolve.
+    ///
+    /// Lists the tx name, the TIR version and every param, sorted by name,
+    /// with its type and supplied value. Params without a value are marked
+    /// `MISSING`. Meant for logs and dry-run output; the format is not stable.
+    ///
+    /// # Example
+    ///
+    /// ```text
+    /// tx: transfer (tir v1beta0)
+    ///   quantity: Integer = 100
+    ///   receiver: Address = MISSING
+    /// ```
+    pub fn describe(&self) -> String {
+        let mut out = format!("tx: {} (tir {})\n", self.tx, self.tir.version);
+
+        let mut params: Vec<_> = self.params.iter().collect();
+        params.sort_by_key(|(name, _)| name.as_str());
+
+        for (name, ty) in params {
+            let value = match self.args.get(name) {
+                Some(value) => value.to_string(),
+                None => "MISSING".to_string(),
+            };
+
+            out.push_str(&format!("  {name}: {} = {value}\n", ty.label()));
+        }
+
+        out
+    }
+
+    /// Returns the input queries the resolver will run for this tx.
+    ///
+    /// Extracted from the TIR, with the args set so far substituted into the
+    /// query criteria. A wallet can use this to check it holds UTxOs that
+    /// satisfy each input before resolving.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidTir` if the TIR payload can't be decoded.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for query in invocation.input_queries()? {
+    ///     println!("{}: {:?}", query.name, query.address);
+    /// }
+    /// ```
+    pub fn input_queries(&self) -> Result<Vec<InputQuery>, Error> {
+        let ir = tir::decode(&self.tir)?;
+        let mut queries = tir::input_queries(&ir);
+
+        for query in queries.iter_mut() {
+            let exprs = [&mut query.address, &mut query.min_amount, &mut query.r#ref];
+
+            for expr in exprs.into_iter().flatten() {
+                tir::substitute_params(expr, &self.args);
+            }
+        }
+
+        Ok(queries)
+    }
+
+    /// Returns the params the compiled TIR expects, as declared in the IR.
+    ///
+    /// Decoded locally, without a network call. Comparing these against
+    /// `params()` catches a TII whose schemas drifted from the IR it ships.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidTir` if the TIR payload can't be decoded.
+    pub fn tir_params(&self) -> Result<Vec<TirParam>, Error> {
+        let ir = tir::decode(&self.tir)?;
+        Ok(tir::params(&ir))
+    }
+
+    /// Maps a resolve error back to the param it is about, for highlighting
+    /// the matching form field.
+    ///
+    /// Handles `MissingTxArg`, keyed by the missing param, and
+    /// `InputNotResolved`, attributed to the param the failed input's address
+    /// comes from (read from the TIR, or by matching the queried address
+    /// against the args). Returns the param name and a message to show next
+    /// to it, or `None` if the error can't be tied to a param.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if let Err(err) = client.resolve(invocation.clone().into_resolve_request()?).await {
+    ///     if let Some((param, message)) = invocation.field_error(&err) {
+    ///         form.set_error(&param, &message);
+    ///     }
+    /// }
+    /// ```
+    pub fn field_error(&self, error: &crate::trp::Error) -> Option<(String, String)> {
+        match error {
+            crate::trp::Error::MissingTxArg(diagnostic) => {
+                let key = self.declared_param(&diagnostic.key)?;
+
+                Some((key.clone(), error.to_string()))
+            }
+            crate::trp::Error::InputNotResolved(diagnostic) => {
+                let from_tir = tir::decode(&self.tir).ok().and_then(|ir| {
+                    let query = tir::input_queries(&ir)
+                        .into_iter()
+                        .find(|x| x.name == diagnostic.name)?;
+
+                    let mut params: Vec<_> = tir::referenced_params(query.address.as_ref()?)
+                        .iter()
+                        .filter_map(|x| self.declared_param(x).cloned())
+                        .collect();
+
+                    params.sort();
+                    params.into_iter().next()
+                });
+
+                let from_args = || {
+                    let address = diagnostic.query.address.as_deref()?;
+
+                    let mut params: Vec<_> = self
+                        .args
+                        .iter()
+                        .filter(|(_, v)| v.as_str() == Some(address))
+                        .map(|(k, _)| k.clone())
+                        .collect();
+
+                    params.sort();
+                    params.into_iter().next()
+                };
+
+                let param = from_tir.or_else(from_args)?;
+
+                Some((param, diagnostic.hint()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Encodes the tx selection, profile and explicitly set args as a
+    /// compact, URL-safe link.
+    ///
+    /// The link is the base64url encoding of a key-sorted JSON object, so the
+    /// same setup always yields the same link. The TIR is not included, it is
+    /// taken from the protocol when the link is opened with
+    /// [`Protocol::from_link`]. Schema defaults and profile values aren't
+    /// included either, as opening the link applies them again.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let link = invocation.to_link();
+    /// let shared = protocol.from_link(&link)?;
+    /// ```
+    pub fn to_link(&self) -> String {
+        let args: serde_json::Map<_, _> = self
+            .args
+            .iter()
+            .filter(|(k, _)| self.explicit_args.contains(*k))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        let link = json!({
+            "tx": self.tx,
+            "profile": self.profile,
+            "args": args,
+        });
+
+        let bytes = serde_json::to_vec(&canonical_json(link)).unwrap_or_default();
+
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    /// Sets a single argument value.
+    ///
+    /// A numeric string given for an `Integer` param, or a `"true"`/`"false"`
+    /// (or `"1"`/`"0"`) string given for a `Boolean` one (as delivered by web
+    /// forms, CLIs or env vars), is parsed when the resolve request is built.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The parameter name, spelled according to the
+    ///   [`CasePolicy`] (case-insensitive by default)
+    /// * `value` - The JSON value to set
+    pub fn set_arg(&mut self, name: &str, value: serde_json::Value) {
+        let name = self.arg_name(name);
+        self.explicit_args.insert(name.clone());
+        self.args.insert(name, value);
+    }
+
+    /// Finds the declared param `name` refers to, ignoring case.
+    fn declared_param(&self, name: &str) -> Option<&String> {
+        self.params.keys().find(|x| x.eq_ignore_ascii_case(name))
+    }
+
+    /// Spells an arg name according to the case policy.
+    fn arg_name(&self, name: &str) -> String {
+        match self.case_policy {
+            CasePolicy::Lowercase => name.to_lowercase(),
+            CasePolicy::Preserve => name.to_string(),
+            CasePolicy::MatchDeclared => self
+                .params
+                .keys()
+                .find(|x| x.eq_ignore_ascii_case(name))
+                .cloned()
+                .unwrap_or_else(|| name.to_string()),
+        }
+    }
+
+    /// Overrides the protocol's [`CasePolicy`] for this invocation (builder
+    /// pattern). Applies to args set from then on.
+    pub fn with_case_policy(mut self, policy: CasePolicy) -> Self {
+        self.case_policy = policy;
+        self
+    }
+
+    /// Sets multiple argument values at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - A map of argument names to values
+    pub fn set_args(&mut self, args: ArgMap) {
+        self.explicit_args.extend(args.keys().cloned());
+        self.args.extend(args);
+    }
+
+    /// Sets argument values from a CBOR-encoded bundle.
+    ///
+    /// The bundle must be a CBOR map of param names to values. Byte strings
+    /// become hex strings, the same form the JSON path expects for bytes, so
+    /// large datums and scripts can be passed without hex-encoding them first.
+    /// Nothing is set if the bundle fails to decode.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The CBOR encoding of the arg map
+    ///
+    /// # Errors
+    ///
+    /// `Error::InvalidCborArgs` if the bytes aren't a single CBOR map.
+    pub fn set_args_cbor(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let Value::Object(args) = cbor::to_json(bytes).map_err(Error::InvalidCborArgs)? else {
+            return Err(Error::InvalidCborArgs("expected a map of args".to_string()));
+        };
+
+        for (name, value) in args {
+            self.set_arg(&name, value);
+        }
+
+        Ok(())
+    }
+
+    /// Sets argument values from environment variables named by convention.
+    ///
+    /// Every variable whose name starts with `prefix` is matched against the
+    /// params by the rest of its name, case-insensitively, so with the prefix
+    /// `TX3_ARG_` the variable `TX3_ARG_SENDER` binds the `sender` param. The
+    /// text is interpreted according to the param type: integers and booleans
+    /// are parsed, bytes, addresses and UTxO refs are taken as is, and compound
+    /// types are read as JSON. Variables that match no param are ignored.
+    /// Nothing is set if any matched variable fails to parse.
+    ///
+    /// # Errors
+    ///
+    /// `Error::InvalidArg` if a matched variable's value doesn't fit the type
+    /// of its param.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // TX3_ARG_QUANTITY=100 TX3_ARG_SENDER=addr1...
+    /// invocation.bind_env("TX3_ARG_")?;
+    /// ```
+    pub fn bind_env(&mut self, prefix: &str) -> Result<(), Error> {
+        let mut bound = Vec::new();
+
+        for (key, raw) in std::env::vars_os() {
+            let (Some(key), Some(raw)) = (key.to_str(), raw.to_str()) else {
+                continue;
+            };
+
+            let Some(name) = key.strip_prefix(prefix).map(str::to_lowercase) else {
+                continue;
+            };
+
+            let Some(ty) = self.params.get(&name) else {
+                continue;
+            };
+
+            let value = env_arg(ty, raw).map_err(|reason| Error::InvalidArg {
+                key: name.clone(),
+                reason: format!("from env var `{key}`: {reason}"),
+            })?;
+
+            bound.push((name, value));
+        }
+
+        for (name, value) in bound {
+            self.set_arg(&name, value);
+        }
+
+        Ok(())
+    }
+
+    /// Sets a single argument value (builder pattern).
+    ///
+    /// This is the builder-pattern variant of `set_arg`, allowing chained calls.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The parameter name (case-insensitive)
+    /// * `value` - The JSON value to set
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn with_arg(mut self, name: &str, value: serde_json::Value) -> Self {
+        self.set_arg(name, value);
+        self
+    }
+
+    /// Sets the addresses of several parties at once (builder pattern).
+    ///
+    /// Each party name is matched case-insensitively and set as its arg like
+    /// `set_arg` would. Nothing is set unless every entry is valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnknownParty` if a name is not a party of the protocol,
+    /// or `Error::InvalidArg` if a value is not a valid address.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let invocation = invocation.with_parties(HashMap::from([
+    ///     ("sender".to_string(), sender_address),
+    ///     ("receiver".to_string(), receiver_address),
+    /// ]))?;
+    /// ```
+    pub fn with_parties(mut self, parties: HashMap<String, String>) -> Result<Self, Error> {
+        for (name, address) in parties.iter() {
+            if !self.parties.contains(&name.to_lowercase()) {
+                return Err(Error::UnknownParty(name.clone()));
+            }
+
+            if let Err(e) = address.parse::<pallas_addresses::Address>() {
+                return Err(Error::InvalidArg {
+                    key: name.clone(),
+                    reason: format!("not a valid address: {e}"),
+                });
+            }
+        }
+
+        for (name, address) in parties {
+            self.set_arg(&name, Value::String(address));
+        }
+
+        Ok(self)
+    }
+
+    /// Sets multiple argument values at once (builder pattern).
+    ///
+    /// This is the builder-pattern variant of `set_args`, allowing chained calls.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - A map of argument names to values
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn with_args(mut self, args: ArgMap) -> Self {
+        self.set_args(args);
+        self
+    }
+
+    /// Sets an asset argument from its structured form (builder pattern).
+    ///
+    /// The asset name is normalized to hex whichever encoding it was given
+    /// in, so callers don't need to know what the resolver expects.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The parameter name (case-insensitive)
+    /// * `asset` - The asset policy, tagged name and amount
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidAssetName` if the policy isn't a 28-byte hex
+    /// script hash (or empty, for ADA), the name is tagged as hex but isn't,
+    /// or the name exceeds 32 bytes.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use tx3_sdk::core::{AssetArg, AssetName};
+    ///
+    /// let invocation = invocation.with_asset_arg("token", &AssetArg {
+    ///     policy: "a0028f350aaabe0545fdcb56b039bfb08e4bb4d8c4d7c3c7d481c235".to_string(),
+    ///     name: AssetName::Utf8("HOSKY".to_string()),
+    ///     amount: 1_000,
+    /// })?;
+    /// ```
+    pub fn with_asset_arg(mut self, name: &str, asset: &AssetArg) -> Result<Self, Error> {
+        let invalid = |source| Error::InvalidAssetName {
+            key: name.to_lowercase(),
+            source,
+        };
+
+        asset.check_policy().map_err(invalid)?;
+        let asset_name = asset.name.to_hex().map_err(invalid)?;
+
+        let value = json!({
+            "policy": asset.policy.to_lowercase(),
+            "name": asset_name,
+            "amount": asset.amount,
+        });
+
+        self.set_arg(name, value);
+        Ok(self)
+    }
+
+    /// Sets the UTxO selection strategy hint sent with the resolve request
+    /// (builder pattern).
+    ///
+    /// Defaults to [`SelectionStrategy::Auto`], which leaves the choice to
+    /// the resolver.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use tx3_sdk::trp::SelectionStrategy;
+    ///
+    /// let invocation = invocation.with_selection_strategy(SelectionStrategy::SmallestFirst);
+    /// ```
+    pub fn with_selection_strategy(mut self, strategy: SelectionStrategy) -> Self {
+        self.selection_strategy = strategy;
+        self
+    }
+
+    /// Attaches transaction metadata under `label` (builder pattern).
+    ///
+    /// The metadata goes in the resolve request for the resolver to include
+    /// in the built tx; a second value for the same label replaces the
+    /// first. JSON maps onto metadata as in CIP-25: objects become maps,
+    /// arrays lists, integers ints and strings text.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidMetadata` if the value doesn't fit the ledger's
+    /// metadata rules: a string or object key longer than 64 bytes, a
+    /// non-integer number, or a boolean or null, which metadata can't hold.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let invocation = invocation.with_metadata(674, json!({ "msg": ["order #42"] }))?;
+    /// ```
+    pub fn with_metadata(mut self, label: u64, value: Value) -> Result<Self, Error> {
+        check_metadata(&value).map_err(|reason| Error::InvalidMetadata { label, reason })?;
+
+        self.metadata.insert(label, value);
+        Ok(self)
+    }
+
+    /// Designates the UTxO that pays the fee (builder pattern).
+    ///
+    /// Sent to the resolver as a hint, for wallets that segregate a dedicated
+    /// fee UTxO. By default the resolver picks the fee inputs itself.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let invocation = invocation.with_fee_input(format!("{fee_tx_hash}#0"));
+    /// ```
+    pub fn with_fee_input(mut self, utxo: UtxoRef) -> Self {
+        self.fee_input = Some(utxo);
+        self
+    }
+
+    /// Resolves against the chain as of `slot` instead of the tip (builder
+    /// pattern).
+    ///
+    /// Only honored by gateways that keep historical UTxO sets; useful for
+    /// reproducible resolves in tests.
+    pub fn at_slot(mut self, slot: u64) -> Self {
+        self.at = Some(ResolvePoint {
+            slot,
+            block_hash: None,
+        });
+        self
+    }
+
+    /// Resolves against a specific chain point instead of the tip (builder
+    /// pattern).
+    ///
+    /// Like [`Invocation::at_slot`], with the block hash pinning the fork.
+    pub fn at_point(mut self, block_hash: &str, slot: u64) -> Self {
+        self.at = Some(ResolvePoint {
+            slot,
+            block_hash: Some(block_hash.to_string()),
+        });
+        self
+    }
+
+    /// Sets an extra top-level field on the resolve request (builder pattern).
+    ///
+    /// Lets early adopters pass experimental gateway options before the SDK
+    /// grows a typed field for them. Keys that clash with a typed field
+    /// (`args`, `tir`, `env`, `selectionStrategy`, `at`, `metadata`,
+    /// `feeInput`) are ignored, since typed fields always take precedence.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The top-level field name, sent as-is
+    /// * `value` - The JSON value to send
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let invocation = invocation.with_extra("costModelOverride", json!({ "plutusV3": [] }));
+    /// ```
+    pub fn with_extra(mut self, key: &str, value: Value) -> Self {
+        if !crate::trp::TYPED_RESOLVE_FIELDS.contains(&key) {
+            self.extra.insert(key.to_string(), value);
+        }
+
+        self
+    }
+
+    /// Swaps the profile applied to this invocation.
+    ///
+    /// Values that came from the previous profile are dropped and replaced
+    /// by the new profile's environment and parties, falling back to schema
+    /// defaults. Arguments set through
+    /// `set_arg`, `set_args`, `with_arg` or `with_args` are kept as-is, even
+    /// when the new profile defines the same key.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocol` - The protocol this invocation was created from
+    /// * `profile` - The name of the profile to apply
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnknownProfile` if the profile is not found, or
+    /// `Error::UnknownProfileArg` if strict profile checks reject it. In both
+    /// cases the invocation is left untouched.
+    pub fn reapply_profile(&mut self, protocol: &Protocol, profile: &str) -> Result<(), Error> {
+        let name = profile;
+        let profile = protocol.ensure_profile(name)?;
+        protocol.check_profile_args(name, profile, &self.params)?;
+
+        let explicit = &self.explicit_args;
+        self.args.retain(|key, _| explicit.contains(key));
+        self.network = None;
+
+        for (key, value) in self.defaults.iter() {
+            if !self.explicit_args.contains(key) {
+                self.args.insert(key.clone(), value.clone());
+            }
+        }
+
+        self.apply_profile(name, profile);
+
+        Ok(())
+    }
+
+    fn apply_profile(&mut self, name: &str, profile: &Profile) {
+        let env = profile.environment.as_object().into_iter().flatten();
+        let parties = profile
+            .parties
+            .iter()
+            .map(|(key, value)| (key.to_lowercase(), json!(value)));
+
+        let values = env.map(|(k, v)| (k.clone(), v.clone())).chain(parties);
+
+        for (key, value) in values {
+            if !self.explicit_args.contains(&key) {
+                self.args.insert(key, value);
+            }
+        }
+
+        self.network = profile.network.clone();
+        self.profile = Some(name.to_string());
+    }
+
+    /// Returns the args as they stand: schema defaults, then profile values,
+    /// then args set explicitly, each layer overriding the previous one.
+    ///
+    /// These are the values sent on resolve, before string coercion of
+    /// `Integer` and `Boolean` params; see
+    /// [`Invocation::effective_resolve_params`] for the exact request.
+    pub fn effective_args(&self) -> &ArgMap {
+        &self.args
+    }
+
+    /// Builds the resolve request `into_resolve_request` would, without
+    /// consuming the invocation.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Invocation::into_resolve_request`].
+    pub fn effective_resolve_params(&self) -> Result<crate::trp::ResolveParams, Error> {
+        self.clone().into_resolve_request()
+    }
+
+    /// Converts this invocation into a TRP resolve request.
+    ///
+    /// This method consumes the invocation and creates the parameters needed
+    /// to call the TRP `resolve` method.
+    ///
+    /// # Returns
+    ///
+    /// Returns `ResolveParams` that can be passed to `trp::Client::resolve`.
+    ///
+    /// `Address` args are sent in a single canonical form: bech32 for
+    /// Shelley and stake addresses, base58 for Byron ones, whether they were
+    /// given in that form or as raw hex.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidAddress` if an `Address` arg isn't a valid
+    /// address, `Error::InvalidAddressKind` if it isn't of the
+    /// `x-address-kind` its schema requires, `Error::NetworkMismatch` if the
+    /// selected profile declares a network and an `Address` argument belongs
+    /// to a different one, or
+    /// `Error::InvalidArg` if a string given for an `Integer` or `Boolean`
+    /// param can't be parsed as one, or an arg breaks the constraints of its
+    /// param.
+    pub fn into_resolve_request(mut self) -> Result<crate::trp::ResolveParams, Error> {
+        self.prepare_args()?;
+        self.canonicalize_addresses()?;
+        self.check_address_kinds()?;
+        self.check_address_networks()?;
+
+        let args = self.args.clone().into_iter().collect();
+
+        let tir = self.tir.clone();
+
+        // We're already merging env into params / args, no need to send it independently.
+        // Having both mechanism is a footgun. We should revisit either the TRP schema to
+        // remove the option or split how we send the env in the SDK.
+        let mut params = crate::trp::ResolveParams::new(tir, args);
+        params.extra = self.extra;
+        params.selection_strategy = self.selection_strategy;
+        params.at = self.at;
+        params.metadata = self.metadata;
+        params.fee_input = self.fee_input;
+
+        Ok(params)
+    }
+
+    /// Coerces strings supplied for `Integer` and `Boolean` params into JSON
+    /// numbers and booleans, as web forms and env vars deliver them as
+    /// strings, then checks args against the declared constraints.
+    fn prepare_args(&mut self) -> Result<(), Error> {
+        for (key, ty) in self.params.iter() {
+            let Some(Value::String(raw)) = self.args.get(key) else {
+                continue;
+            };
+
+            let coerced = match ty {
+                ParamType::Integer => coerce_integer(raw).map(Value::Number),
+                ParamType::Boolean => coerce_boolean(raw).map(Value::Bool),
+                _ => continue,
+            };
+
+            let value = coerced.map_err(|reason| Error::InvalidArg {
+                key: key.clone(),
+                reason,
+            })?;
+
+            self.args.insert(key.clone(), value);
+        }
+
+        for (key, constraints) in self.constraints.iter() {
+            let Some(value) = self.args.get(key) else {
+                continue;
+            };
+
+            constraints
+                .check(value)
+                .map_err(|reason| Error::InvalidArg {
+                    key: key.clone(),
+                    reason,
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Rewrites string `Address` args, bech32, base58 or hex, into the
+    /// canonical form the resolver expects.
+    fn canonicalize_addresses(&mut self) -> Result<(), Error> {
+        for (key, ty) in self.params.iter() {
+            if !matches!(ty, ParamType::Address) {
+                continue;
+            }
+
+            let Some(Value::String(raw)) = self.args.get(key) else {
+                continue;
+            };
+
+            let canonical = raw
+                .parse::<pallas_addresses::Address>()
+                .ok()
+                .and_then(|x| match x {
+                    pallas_addresses::Address::Byron(x) => Some(x.to_base58()),
+                    other => other.to_bech32().ok(),
+                })
+                .ok_or_else(|| Error::InvalidAddress { key: key.clone() })?;
+
+            self.args.insert(key.clone(), Value::String(canonical));
+        }
+
+        Ok(())
+    }
+
+    /// Checks `Address` args against the `x-address-kind` their schema
+    /// declares.
+    fn check_address_kinds(&self) -> Result<(), Error> {
+        for (key, constraints) in self.constraints.iter() {
+            let Some(expected) = constraints.address_kind else {
+                continue;
+            };
+
+            let Some(address) = self
+                .args
+                .get(key)
+                .and_then(Value::as_str)
+                .and_then(|x| x.parse::<pallas_addresses::Address>().ok())
+            else {
+                continue;
+            };
+
+            if !expected.matches(&address) {
+                return Err(Error::InvalidAddressKind {
+                    key: key.clone(),
+                    expected,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_address_networks(&self) -> Result<(), Error> {
+        let Some(expected) = self.network.as_ref().and_then(Network::address_network) else {
+            return Ok(());
+        };
+
+        for (key, ty) in self.params.iter() {
+            if !matches!(ty, ParamType::Address) {
+                continue;
+            }
+
+            // String args were canonicalized already; values of any other
+            // shape are left for the resolver to reject.
+            let Some(address) = self
+                .args
+                .get(key)
+                .and_then(Value::as_str)
+                .and_then(|x| x.parse::<pallas_addresses::Address>().ok())
+            else {
+                continue;
+            };
+
+            let Some(got) = address.network() else {
+                continue;
+            };
+
+            if got != expected {
+                return Err(Error::NetworkMismatch {
+                    key: key.clone(),
+                    expected: network_name(expected),
+                    got: network_name(got),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn coerce_integer(raw: &str) -> Result<serde_json::Number, String> {
+    let trimmed = raw.trim();
+
+    if let Ok(x) = trimmed.parse::<i64>() {
+        return Ok(x.into());
+    }
+
+    if let Ok(x) = trimmed.parse::<u64>() {
+        return Ok(x.into());
+    }
+
+    if trimmed.parse::<i128>().is_ok() {
+        return Err(format!("`{raw}` is out of the supported integer range"));
+    }
+
+    Err(format!("expected an integer, got `{raw}`"))
+}
+
+/// Rebuilds every object of a JSON tree with its keys in sorted order.
+fn canonical_json(value: Value) -> Value {
+    match value {
+        Value::Object(obj) => {
+            let mut entries: Vec<_> = obj.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, canonical_json(v)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(canonical_json).collect()),
+        other => other,
+    }
+}
+
+/// The ledger's size limit for metadata strings and byte strings.
+const METADATA_MAX_STRING_BYTES: usize = 64;
+
+/// Checks a JSON value maps onto transaction metadata.
+fn check_metadata(value: &Value) -> Result<(), String> {
+    let check_text = |text: &str| {
+        if text.len() > METADATA_MAX_STRING_BYTES {
+            return Err(format!(
+                "`{text}` is longer than {METADATA_MAX_STRING_BYTES} bytes"
+            ));
+        }
+
+        Ok(())
+    };
+
+    match value {
+        Value::String(text) => check_text(text),
+        Value::Number(n) if n.is_i64() || n.is_u64() => Ok(()),
+        Value::Number(n) => Err(format!("`{n}` is not an integer")),
+        Value::Array(items) => items.iter().try_for_each(check_metadata),
+        Value::Object(entries) => entries.iter().try_for_each(|(key, value)| {
+            check_text(key)?;
+            check_metadata(value)
+        }),
+        Value::Bool(_) | Value::Null => Err(format!("`{value}` has no metadata encoding")),
+    }
+}
+
+/// Interprets the text of an env var as an arg of the given param type.
+fn env_arg(ty: &ParamType, raw: &str) -> Result<Value, String> {
+    match ty {
+        ParamType::Integer => coerce_integer(raw).map(Value::Number),
+        ParamType::Boolean => coerce_boolean(raw).map(Value::Bool),
+        ParamType::Unit => Ok(Value::Null),
+        ParamType::Bytes | ParamType::Address | ParamType::UtxoRef => {
+            Ok(Value::String(raw.trim().to_string()))
+        }
+        ParamType::Unknown(_) => {
+            Ok(serde_json::from_str(raw).unwrap_or(Value::String(raw.to_string())))
+        }
+        _ => serde_json::from_str(raw).map_err(|e| format!("expected JSON: {e}")),
+    }
+}
+
+fn coerce_boolean(raw: &str) -> Result<bool, String> {
+    match raw.trim().to_lowercase().as_str() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(format!("expected `true`, `false`, `1` or `0`, got `{raw}`")),
+    }
+}
+
+fn network_name(network: pallas_addresses::Network) -> String {
+    match network {
+        pallas_addresses::Network::Mainnet => "mainnet".to_string(),
+        pallas_addresses::Network::Testnet => "testnet".to_string(),
+        pallas_addresses::Network::Other(x) => format!("network {x}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_fixtures::{drifted_transfer, hex_tir, protocol_with_params};
+
+    #[test]
+    fn happy_path_smoke_test() {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let tii = format!("{manifest_dir}/tests/fixtures/transfer.tii");
+
+        let protocol = Protocol::from_file(&tii).unwrap();
+
+        let invoke = protocol.invoke("transfer", Some("preprod")).unwrap();
+
+        let mut invoke = invoke
+            .with_arg("sender", json!(MAINNET_ADDRESS))
+            .with_arg("quantity", json!(100_000_000));
+
+        let all_params: HashSet<_> = invoke.params().keys().collect();
+
+        assert_eq!(all_params.len(), 5);
+        assert!(all_params.contains(&"sender".to_string()));
+        assert!(all_params.contains(&"middleman".to_string()));
+        assert!(all_params.contains(&"receiver".to_string()));
+        assert!(all_params.contains(&"tax".to_string()));
+        assert!(all_params.contains(&"quantity".to_string()));
+
+        let unspecified_params: HashSet<_> = invoke.unspecified_params().map(|(k, _)| k).collect();
+
+        assert_eq!(unspecified_params.len(), 2);
+        assert!(unspecified_params.contains(&"middleman".to_string()));
+        assert!(unspecified_params.contains(&"receiver".to_string()));
+
+        let tx = invoke.into_resolve_request().unwrap();
+
+        dbg!(&tx);
+    }
+
+    const MAINNET_ADDRESS: &str = "addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgse35a3x";
+    const TESTNET_ADDRESS: &str = "addr_test1qz2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgs68faae";
+
+    fn protocol_with_network(network: &str) -> Protocol {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let tii = format!("{manifest_dir}/tests/fixtures/transfer.tii");
+
+        let mut protocol = Protocol::from_file(&tii).unwrap();
+        let profile = protocol.spec.profiles.get_mut("preprod").unwrap();
+        profile.network = Some(serde_json::from_value(json!(network)).unwrap());
+
+        protocol
+    }
+
+    #[test]
+    fn address_args_must_match_profile_network() {
+        let protocol = protocol_with_network("preprod");
+
+        let invoke = protocol
+            .invoke("transfer", Some("preprod"))
+            .unwrap()
+            .with_arg("sender", json!(MAINNET_ADDRESS));
+
+        match invoke.into_resolve_request() {
+            Err(Error::NetworkMismatch { key, expected, got }) => {
+                assert_eq!(key, "sender");
+                assert_eq!(expected, "testnet");
+                assert_eq!(got, "mainnet");
+            }
+            other => panic!("expected network mismatch, got {other:?}"),
+        }
+
+        let invoke = protocol
+            .invoke("transfer", Some("preprod"))
+            .unwrap()
+            .with_arg("sender", json!(TESTNET_ADDRESS));
+
+        assert!(invoke.into_resolve_request().is_ok());
+
+        // a network the SDK doesn't know can't be checked against
+        let protocol = protocol_with_network("devnet");
+
+        let invoke = protocol
+            .invoke("transfer", Some("preprod"))
+            .unwrap()
+            .with_arg("sender", json!(MAINNET_ADDRESS));
+
+        assert!(invoke.into_resolve_request().is_ok());
+    }
+
+    #[test]
+    fn profile_network_is_typed() {
+        let protocol = protocol_with_network("Preprod");
+
+        assert_eq!(
+            protocol.profile_network("preprod").unwrap(),
+            Some(Network::Preprod)
+        );
+        assert!(matches!(
+            protocol.profile_network("missing"),
+            Err(Error::UnknownProfile(_))
+        ));
+
+        let network: Network = serde_json::from_value(json!(42)).unwrap();
+        assert_eq!(network, Network::Custom(42));
+        assert_eq!(serde_json::to_value(network).unwrap(), json!(42));
+        assert_eq!(
+            serde_json::to_value(Network::Preview).unwrap(),
+            json!("preview")
+        );
+
+        let network: Network = serde_json::from_value(json!("testnet")).unwrap();
+        assert_eq!(network.network_id(), Some(0));
+        assert_eq!(Network::Custom(1), Network::Mainnet);
+        assert_ne!(Network::Custom(0), Network::Preview);
+
+        let network: Network = serde_json::from_value(json!("Devnet")).unwrap();
+        assert_eq!(network, Network::Unknown("devnet".to_string()));
+        assert_eq!(network.network_id(), None);
+        assert_eq!(serde_json::to_value(network).unwrap(), json!("Devnet"));
+    }
+
+    #[test]
+    fn address_args_are_canonicalized() {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let tii = format!("{manifest_dir}/tests/fixtures/transfer.tii");
+        let protocol = Protocol::from_file(&tii).unwrap();
+
+        let raw = TESTNET_ADDRESS
+            .parse::<pallas_addresses::Address>()
+            .unwrap()
+            .to_hex();
+
+        let request = protocol
+            .invoke("transfer", Some("preprod"))
+            .unwrap()
+            .with_arg("sender", json!(raw))
+            .with_arg("receiver", json!(TESTNET_ADDRESS))
+            .into_resolve_request()
+            .unwrap();
+
+        assert_eq!(request.args["sender"], json!(TESTNET_ADDRESS));
+        assert_eq!(request.args["receiver"], json!(TESTNET_ADDRESS));
+
+        let err = protocol
+            .invoke("transfer", Some("preprod"))
+            .unwrap()
+            .with_arg("sender", json!("addr1abc"))
+            .into_resolve_request()
+            .unwrap_err();
+
+        assert!(matches!(err, Error::InvalidAddress { key } if key == "sender"));
+    }
+
+    #[test]
+    fn unknown_address_kinds_are_rejected() {
+        let params = json!({
+            "type": "object",
+            "properties": {
+                "owner": {
+                    "$ref": "https://tx3.land/specs/v1beta0/tii#/$defs/Address",
+                    "x-address-kind": "multisig"
+                }
+            }
+        });
+
+        let protocol = protocol_with_params(params).build().unwrap();
+
+        assert!(matches!(
+            protocol.invoke("tx", None),
+            Err(Error::UnknownAddressKind { key, .. }) if key == "owner"
+        ));
+    }
+
+    #[test]
+    fn address_kind_constraints_are_checked() {
+        let params = json!({
+            "type": "object",
+            "properties": {
+                "owner": {
+                    "$ref": "https://tx3.land/specs/v1beta0/tii#/$defs/Address",
+                    "x-address-kind": "enterprise"
+                }
+            }
+        });
+
+        let protocol = protocol_with_params(params).build().unwrap();
+
+        let base = match TESTNET_ADDRESS
+            .parse::<pallas_addresses::Address>()
+            .unwrap()
+        {
+            pallas_addresses::Address::Shelley(x) => x,
+            other => panic!("expected a shelley address, got {other:?}"),
+        };
+
+        let enterprise = pallas_addresses::ShelleyAddress::new(
+            base.network(),
+            base.payment().clone(),
+            pallas_addresses::ShelleyDelegationPart::Null,
+        )
+        .to_bech32()
+        .unwrap();
+
+        let ok = protocol
+            .invoke("tx", None)
+            .unwrap()
+            .with_arg("owner", json!(enterprise))
+            .into_resolve_request();
+        assert!(ok.is_ok());
+
+        let err = protocol
+            .invoke("tx", None)
+            .unwrap()
+            .with_arg("owner", json!(TESTNET_ADDRESS))
+            .into_resolve_request()
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::InvalidAddressKind { key, expected: AddressKind::Enterprise } if key == "owner"
+        ));
+    }
+
+    #[test]
+    fn network_check_skipped_without_hint() {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let tii = format!("{manifest_dir}/tests/fixtures/transfer.tii");
+        let protocol = Protocol::from_file(&tii).unwrap();
+
+        let invoke = protocol
+            .invoke("transfer", Some("preprod"))
+            .unwrap()
+            .with_arg("sender", json!(MAINNET_ADDRESS));
+
+        assert!(invoke.into_resolve_request().is_ok());
+    }
+
+    #[test]
+    fn reapply_profile_keeps_user_args() {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let tii = format!("{manifest_dir}/tests/fixtures/transfer.tii");
+
+        let mut protocol = Protocol::from_file(&tii).unwrap();
+        protocol.spec.profiles.insert(
+            "other".to_string(),
+            Profile {
+                environment: json!({ "tax": 1 }),
+                parties: HashMap::from([("receiver".to_string(), TESTNET_ADDRESS.to_string())]),
+                ..Default::default()
+            },
+        );
+
+        let mut invoke = protocol
+            .invoke("transfer", Some("preprod"))
+            .unwrap()
+            .with_arg("quantity", json!(100))
+            .with_arg("sender", json!("addr1abc"));
+
+        invoke.reapply_profile(&protocol, "other").unwrap();
+
+        assert_eq!(invoke.args["tax"], json!(1));
+        assert_eq!(invoke.args["receiver"], json!(TESTNET_ADDRESS));
+        assert_eq!(invoke.args["quantity"], json!(100));
+        assert_eq!(invoke.args["sender"], json!("addr1abc"));
+
+        invoke.reapply_profile(&protocol, "local").unwrap();
+
+        assert!(!invoke.args.contains_key("tax"));
+        assert!(!invoke.args.contains_key("receiver"));
+        assert_eq!(invoke.args["quantity"], json!(100));
+
+        assert!(matches!(
+            invoke.reapply_profile(&protocol, "missing"),
+            Err(Error::UnknownProfile(_))
+        ));
+        assert_eq!(invoke.args["quantity"], json!(100));
+    }
+
+    /// Serves one TII response and returns a URL for it.
+    fn serve_tii(status: &'static str, content_type: &str, body: &str) -> String {
+        use crate::test_server::{serve, Reply};
+
+        let reply = Reply::new(status)
+            .header("content-type", content_type)
+            .body(body)
+            .sized();
+
+        format!("{}/protocol.tii", serve(vec![reply]).url)
+    }
+
+    #[tokio::test]
+    async fn from_url_fetches_and_parses() {
+        let body = include_str!("../../tests/fixtures/transfer.tii");
+        let url = serve_tii("200 OK", "text/plain", body);
+
+        let protocol = Protocol::from_url(&url, DEFAULT_MAX_TII_BYTES)
+            .await
+            .unwrap();
+        assert!(protocol.txs().contains_key("transfer"));
+    }
+
+    #[tokio::test]
+    async fn from_url_caps_the_body() {
+        use crate::test_server::{serve, Reply};
+
+        let body = include_str!("../../tests/fixtures/transfer.tii");
+        let limit = body.len() - 1;
+
+        let url = serve_tii("200 OK", "application/json", body);
+        assert!(matches!(
+            Protocol::from_url(&url, limit).await,
+            Err(Error::FileTooLarge { limit: x }) if x == limit
+        ));
+
+        // without a content-length the cap applies while streaming
+        let reply = Reply::json(body);
+        let url = format!("{}/protocol.tii", serve(vec![reply]).url);
+        assert!(matches!(
+            Protocol::from_url(&url, limit).await,
+            Err(Error::FileTooLarge { limit: x }) if x == limit
+        ));
+    }
+
+    #[tokio::test]
+    async fn from_url_rejects_errors_and_html() {
+        let url = serve_tii("404 Not Found", "application/json", "{}");
+        assert!(matches!(
+            Protocol::from_url(&url, DEFAULT_MAX_TII_BYTES).await,
+            Err(Error::HttpError(404, _))
+        ));
+
+        let url = serve_tii("200 OK", "text/html", "<html></html>");
+        assert!(matches!(
+            Protocol::from_url(&url, DEFAULT_MAX_TII_BYTES).await,
+            Err(Error::UnexpectedContentType(_))
+        ));
+    }
+
+    #[test]
+    fn schema_defaults_prefill_args() {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let tii = format!("{manifest_dir}/tests/fixtures/transfer.tii");
+
+        let mut protocol = Protocol::from_file(&tii).unwrap();
+        protocol.spec.environment.as_mut().unwrap()["properties"]["tax"]["default"] = json!(7);
+        protocol
+            .spec
+            .transactions
+            .get_mut("transfer")
+            .unwrap()
+            .params["properties"]["quantity"]["default"] = json!(42);
+
+        let mut invoke = protocol.invoke("transfer", None).unwrap();
+        assert_eq!(invoke.args["tax"], json!(7));
+        assert_eq!(invoke.args["quantity"], json!(42));
+
+        let unspecified: HashSet<_> = invoke
+            .unspecified_params()
+            .map(|(k, _)| k.clone())
+            .collect();
+        assert!(!unspecified.contains("tax"));
+        assert!(!unspecified.contains("quantity"));
+
+        // Profile values win over defaults, user args win over both.
+        let mut invoke = protocol
+            .invoke("transfer", Some("preprod"))
+            .unwrap()
+            .with_arg("quantity", json!(1));
+        assert_eq!(invoke.args["tax"], json!(5000000));
+        assert_eq!(invoke.args["quantity"], json!(1));
+
+        invoke.reapply_profile(&protocol, "local").unwrap();
+        assert_eq!(invoke.args["tax"], json!(7));
+        assert_eq!(invoke.args["quantity"], json!(1));
+    }
+
+    #[test]
+    fn strict_profiles_reject_unknown_keys() {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let tii = format!("{manifest_dir}/tests/fixtures/transfer.tii");
+
+        let mut protocol = Protocol::from_file(&tii).unwrap();
+        protocol.spec.profiles.insert(
+            "drifted".to_string(),
+            Profile {
+                environment: json!({ "tax": 1, "fee_rate": 2 }),
+                ..Default::default()
+            },
+        );
+
+        // Lenient by default: the stray key is merged as-is.
+        let invoke = protocol.invoke("transfer", Some("drifted")).unwrap();
+        assert_eq!(invoke.args["fee_rate"], json!(2));
+
+        let protocol = protocol.strict_profiles(true);
+
+        match protocol.invoke("transfer", Some("drifted")) {
+            Err(Error::UnknownProfileArg { profile, key }) => {
+                assert_eq!(profile, "drifted");
+                assert_eq!(key, "fee_rate");
+            }
+            other => panic!("expected unknown profile arg, got {other:?}"),
+        }
+
+        let mut invoke = protocol.invoke("transfer", Some("preprod")).unwrap();
+        assert!(matches!(
+            invoke.reapply_profile(&protocol, "drifted"),
+            Err(Error::UnknownProfileArg { .. })
+        ));
+        assert_eq!(invoke.args["tax"], json!(5000000));
+    }
+
+    #[test]
+    fn resolve_ref_and_top_level_refs() {
+        let protocol = Protocol::builder()
+            .tii_version("v1beta0")
+            .name("refs")
+            .component(
+                "Env",
+                json!({ "type": "object", "properties": { "tax": { "type": "integer" } } }),
+            )
+            .component("Params", json!({ "$ref": "#/components/schemas/Inner" }))
+            .component(
+                "Inner",
+                json!({ "type": "object", "properties": { "flag": { "type": "boolean" } } }),
+            )
+            .component("Loop", json!({ "$ref": "#/components/schemas/Loop" }))
+            .environment(json!({ "$ref": "#/components/schemas/Env" }))
+            .transaction(
+                "tx",
+                hex_tir("00"),
+                json!({ "$ref": "#/components/schemas/Params" }),
+            )
+            .transaction(
+                "looping",
+                hex_tir("00"),
+                json!({ "$ref": "#/components/schemas/Loop" }),
+            )
+            .build()
+            .unwrap();
+
+        assert!(protocol.resolve_ref("#/components/schemas/Env").is_some());
+        assert!(protocol
+            .resolve_ref("#/components/schemas/Missing")
+            .is_none());
+        assert!(protocol
+            .resolve_ref("https://tx3.land/specs/v1beta0/tii#/$defs/Bytes")
+            .is_none());
+
+        let mut invoke = protocol.invoke("tx", None).unwrap();
+        assert!(matches!(invoke.params()["tax"], ParamType::Integer));
+        assert!(matches!(invoke.params()["flag"], ParamType::Boolean));
+
+        let mut invoke = protocol.invoke("looping", None).unwrap();
+        assert_eq!(invoke.params().len(), 1);
+    }
+
+    #[test]
+    fn structurally_empty_tiis_fail_to_load() {
+        let tii = |version: &str, name: &str, transactions: Value| {
+            json!({
+                "tii": { "version": version },
+                "protocol": { "name": name, "version": "1.0.0" },
+                "transactions": transactions
+            })
+        };
+
+        let err = Protocol::from_json(tii("v1beta0", "p", json!({}))).unwrap_err();
+        assert!(matches!(err, Error::MissingTransactions));
+
+        let err = Protocol::from_json(tii("", "p", json!({}))).unwrap_err();
+        assert!(matches!(err, Error::MissingField(f) if f == "tii.version"));
+
+        let err = Protocol::from_json(tii("v1beta0", " ", json!({}))).unwrap_err();
+        assert!(matches!(err, Error::MissingField(f) if f == "protocol.name"));
+    }
+
+    #[test]
+    fn from_json_strict_rejects_unknown_fields() {
+        let tii: Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        assert!(Protocol::from_json_strict(tii.clone()).is_ok());
+
+        let mut typo = tii.clone();
+        typo["transaction"] = typo["transactions"].clone();
+        match Protocol::from_json_strict(typo.clone()) {
+            Err(Error::UnknownField(path)) => assert_eq!(path, "transaction"),
+            other => panic!("expected unknown field, got {other:?}"),
+        }
+        assert!(Protocol::from_json(typo).is_ok());
+
+        let mut nested = tii;
+        nested["profiles"]["preprod"]["enviroment"] = json!({});
+        match Protocol::from_json_strict(nested) {
+            Err(Error::UnknownField(path)) => assert_eq!(path, "profiles.preprod.enviroment"),
+            other => panic!("expected unknown field, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn deep_and_cyclic_schemas_are_rejected() {
+        let mut nested = json!({ "type": "integer" });
+        for _ in 0..10 {
+            nested = json!({ "type": "array", "items": nested });
+        }
+
+        let params = json!({ "type": "object", "properties": { "deep": nested } });
+
+        let protocol = protocol_with_params(params.clone()).build().unwrap();
+        assert!(protocol.invoke("tx", None).is_ok());
+
+        let protocol = protocol_with_params(params)
+            .build()
+            .unwrap()
+            .max_schema_depth(5);
+        assert!(matches!(
+            protocol.invoke("tx", None),
+            Err(Error::SchemaTooDeep(5))
+        ));
+
+        let cyclic = json!({
+            "type": "object",
+            "properties": { "node": { "$ref": "#/components/schemas/Node" } }
+        });
+        let protocol = protocol_with_params(cyclic)
+            .component(
+                "Node",
+                json!({
+                    "type": "object",
+                    "properties": { "next": { "$ref": "#/components/schemas/Node" } }
+                }),
+            )
+            .build()
+            .unwrap();
+        assert!(matches!(
+            protocol.invoke("tx", None),
+            Err(Error::SchemaTooDeep(DEFAULT_MAX_SCHEMA_DEPTH))
+        ));
+    }
+
+    #[test]
+    fn asset_args_normalize_name_to_hex() {
+        use crate::core::{AssetError, AssetName};
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let protocol =
+            Protocol::from_file(format!("{manifest_dir}/tests/fixtures/complex.tii")).unwrap();
+        let invoke = protocol.invoke("complex", None).unwrap();
+
+        let policy = "A0028F350AAABE0545FDCB56B039BFB08E4BB4D8C4D7C3C7D481C235";
+        let asset = |name| AssetArg {
+            policy: policy.to_string(),
+            name,
+            amount: 5,
+        };
+
+        let utf8 = invoke
+            .clone()
+            .with_asset_arg("bag", &asset(AssetName::Utf8("HOSKY".to_string())))
+            .unwrap();
+        let hex = invoke
+            .clone()
+            .with_asset_arg("Bag", &asset(AssetName::Hex("484F534B59".to_string())))
+            .unwrap();
+
+        let expected = json!({
+            "policy": policy.to_lowercase(),
+            "name": "484f534b59",
+            "amount": 5
+        });
+        assert_eq!(utf8.args["bag"], expected);
+        assert_eq!(hex.args["bag"], expected);
+
+        let too_long = asset(AssetName::Utf8("x".repeat(33)));
+        match invoke.clone().with_asset_arg("bag", &too_long) {
+            Err(Error::InvalidAssetName { key, source }) => {
+                assert_eq!(key, "bag");
+                assert_eq!(source, AssetError::NameTooLong(33));
+            }
+            other => panic!("expected invalid asset name, got {other:?}"),
+        }
+
+        let not_hex = asset(AssetName::Hex("HOSKY".to_string()));
+        assert!(matches!(
+            invoke.clone().with_asset_arg("bag", &not_hex),
+            Err(Error::InvalidAssetName {
+                source: AssetError::NameNotHex(_),
+                ..
+            })
+        ));
+
+        let short_policy = AssetArg {
+            policy: "ab01".to_string(),
+            ..asset(AssetName::Utf8("HOSKY".to_string()))
+        };
+        assert!(matches!(
+            invoke.clone().with_asset_arg("bag", &short_policy),
+            Err(Error::InvalidAssetName {
+                source: AssetError::PolicyLength(2),
+                ..
+            })
+        ));
+
+        let ada = AssetArg {
+            policy: String::new(),
+            ..asset(AssetName::Utf8(String::new()))
+        };
+        assert!(invoke.with_asset_arg("bag", &ada).is_ok());
+    }
+
+    #[test]
+    fn extra_fields_are_flattened_into_the_request() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let request = protocol
+            .invoke("transfer", None)
+            .unwrap()
+            .with_arg("quantity", json!(1))
+            .with_extra("costModelOverride", json!({ "plutusV3": [1, 2] }))
+            .with_extra("args", json!("ignored"))
+            .into_resolve_request()
+            .unwrap();
+
+        let wire = serde_json::to_value(&request).unwrap();
+        assert_eq!(wire["costModelOverride"], json!({ "plutusV3": [1, 2] }));
+        assert_eq!(wire["args"]["quantity"], json!(1));
+
+        // Typed fields win even when `extra` is filled directly.
+        let mut request = request;
+        request.extra.insert("tir".to_string(), json!("bogus"));
+        let wire = serde_json::to_value(&request).unwrap();
+        assert_eq!(wire["tir"]["version"], json!("v1beta0"));
+    }
+
+    #[test]
+    fn selection_strategy_is_sent_when_set() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let request = protocol
+            .invoke("transfer", None)
+            .unwrap()
+            .into_resolve_request()
+            .unwrap();
+        let body = serde_json::to_value(&request).unwrap();
+        assert!(body.get("selectionStrategy").is_none());
+
+        let request = protocol
+            .invoke("transfer", None)
+            .unwrap()
+            .with_selection_strategy(SelectionStrategy::MinimizeInputs)
+            .with_extra("selectionStrategy", json!("largest_first"))
+            .into_resolve_request()
+            .unwrap();
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["selectionStrategy"], json!("minimize_inputs"));
+    }
+
+    #[test]
+    fn canonical_hash_ignores_formatting_and_key_order() {
+        let text = include_str!("../../tests/fixtures/transfer.tii");
+        let protocol = Protocol::from_static(text).unwrap();
+
+        // Same document, compacted and with its top-level keys reordered
+        let value: Value = serde_json::from_str(text).unwrap();
+        let mut sections: Vec<_> = value.as_object().unwrap().iter().collect();
+        sections.reverse();
+
+        let body: Vec<_> = sections
+            .into_iter()
+            .map(|(k, v)| format!("{}:{v}", json!(k)))
+            .collect();
+        let reformatted = Protocol::from_string(format!("{{{}}}", body.join(","))).unwrap();
+
+        assert_eq!(protocol.canonical_hash(), reformatted.canonical_hash());
+
+        let mut changed: Value = serde_json::from_str(text).unwrap();
+        changed["protocol"]["name"] = json!("other");
+        let changed = Protocol::from_json(changed).unwrap();
+
+        assert_ne!(protocol.canonical_hash(), changed.canonical_hash());
+    }
+
+    #[test]
+    fn protocols_compare_structurally() {
+        let text = include_str!("../../tests/fixtures/transfer.tii");
+        let protocol = Protocol::from_static(text).unwrap();
+
+        let value: Value = serde_json::from_str(text).unwrap();
+        let reloaded = Protocol::from_json(value.clone())
+            .unwrap()
+            .strict_profiles(true);
+        assert_eq!(protocol, reloaded);
+
+        let mut changed = value;
+        changed["protocol"]["version"] = json!("9.9.9");
+        assert_ne!(protocol, Protocol::from_json(changed).unwrap());
+    }
+
+    #[test]
+    fn component_titles_and_descriptions() {
+        let protocol = Protocol::builder()
+            .tii_version("v1beta0")
+            .name("labels")
+            .component(
+                "Point",
+                json!({ "title": "Point", "description": "A 2D point", "type": "object" }),
+            )
+            .component("Location", json!({ "$ref": "#/components/schemas/Point" }))
+            .component("Bare", json!({ "type": "object" }))
+            .build()
+            .unwrap();
+
+        assert_eq!(protocol.schema_title("Point"), Some("Point"));
+        assert_eq!(
+            protocol.schema_title("#/components/schemas/Location"),
+            Some("Point")
+        );
+        assert_eq!(protocol.schema_description("Location"), Some("A 2D point"));
+        assert_eq!(protocol.schema_title("Bare"), None);
+        assert_eq!(protocol.schema_title("Missing"), None);
+    }
+
+    #[test]
+    fn resolve_point_is_sent_when_set() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let invoke = || protocol.invoke("transfer", None).unwrap();
+
+        let body = serde_json::to_value(invoke().into_resolve_request().unwrap()).unwrap();
+        assert!(body.get("at").is_none());
+
+        let request = invoke().at_slot(42).into_resolve_request().unwrap();
+        let body = serde_json::to_value(request).unwrap();
+        assert_eq!(body["at"], json!({ "slot": 42 }));
+
+        let request = invoke().at_point("bb", 42).into_resolve_request().unwrap();
+        let body = serde_json::to_value(request).unwrap();
+        assert_eq!(body["at"], json!({ "slot": 42, "blockHash": "bb" }));
+    }
+
+    #[test]
+    fn tx_tir_version_lookup() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        assert_eq!(protocol.tx_tir_version("transfer").unwrap(), "v1beta0");
+        assert!(matches!(
+            protocol.tx_tir_version("missing"),
+            Err(Error::UnknownTx(_))
+        ));
+    }
+
+    #[test]
+    fn party_description_lookup() {
+        let protocol = Protocol::builder()
+            .tii_version("v1beta0")
+            .name("parties")
+            .party("sender", Some("Pays for the transfer".to_string()))
+            .party("receiver", None)
+            .build()
+            .unwrap();
+
+        assert_eq!(protocol.parties().len(), 2);
+        assert_eq!(
+            protocol.party_description("sender"),
+            Some("Pays for the transfer")
+        );
+        assert_eq!(protocol.party_description("receiver"), None);
+        assert_eq!(protocol.party_description("missing"), None);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn from_yaml_matches_json() {
+        let yaml = r#"
+# Commented YAML source of truth.
+tii:
+  version: v1beta0
+protocol:
+  name: transfer
+  version: 0.1.0
+environment:
+  type: object
+  properties:
+    tax: { type: integer }
+parties:
+  sender: {}
+transactions:
+  transfer:
+    tir: { content: "00", encoding: hex, version: v1beta0 }
+    params:
+      type: object
+      properties:
+        quantity: { type: integer }
+profiles:
+  preprod:
+    environment: { tax: 5000000 }
+"#;
+
+        let protocol = Protocol::from_yaml(yaml).unwrap();
+        let invoke = protocol.invoke("transfer", Some("preprod")).unwrap();
+
+        assert_eq!(invoke.params.len(), 3);
+        assert_eq!(invoke.args["tax"], json!(5000000));
+
+        assert!(matches!(
+            Protocol::from_yaml("tii: ["),
+            Err(Error::InvalidYaml(_))
+        ));
+    }
+
+    #[test]
+    fn from_static_loads_embedded_tii() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        assert!(protocol.txs().contains_key("transfer"));
+        assert!(protocol.profiles().contains_key("preprod"));
+    }
+
+    #[test]
+    fn param_examples_are_extracted() {
+        let params = json!({
+            "type": "object",
+            "properties": {
+                "quantity": { "type": "integer", "examples": [10, 20] },
+                "label": { "type": "string", "example": "gm" },
+                "free": { "type": "integer" }
+            }
+        });
+
+        let protocol = protocol_with_params(params).build().unwrap();
+        let examples = protocol.invoke("tx", None).unwrap().param_examples();
+
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples["quantity"], vec![json!(10), json!(20)]);
+        assert_eq!(examples["label"], vec![json!("gm")]);
+    }
+
+    #[test]
+    fn param_constraints_are_extracted_and_checked() {
+        let params = json!({
+            "type": "object",
+            "properties": {
+                "quantity": { "type": "integer", "minimum": 0, "maximum": 100.5 },
+                "label": { "type": "string", "maxLength": 4, "pattern": "^[a-z]+$" },
+                "free": { "type": "integer" }
+            }
+        });
+
+        let protocol = protocol_with_params(params).build().unwrap();
+        let invoke = || protocol.invoke("tx", None).unwrap();
+
+        let constraints = invoke().param_constraints("quantity").cloned().unwrap();
+        assert_eq!(constraints.minimum, Some(0));
+        assert_eq!(constraints.maximum, Some(100));
+
+        let label = invoke().param_constraints("label").cloned().unwrap();
+        assert_eq!(label.max_length, Some(4));
+        assert_eq!(label.pattern.as_deref(), Some("^[a-z]+$"));
+        assert!(invoke().param_constraints("free").is_none());
+
+        let ok = invoke()
+            .with_arg("quantity", json!("100"))
+            .with_arg("label", json!("abcd"))
+            .into_resolve_request();
+        assert!(ok.is_ok());
+
+        let err = invoke()
+            .with_arg("quantity", json!(-1))
+            .into_resolve_request()
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidArg { key, .. } if key == "quantity"));
+
+        let err = invoke()
+            .with_arg("label", json!("abcde"))
+            .into_resolve_request()
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidArg { key, .. } if key == "label"));
+    }
+
+    #[test]
+    fn numeric_strings_are_coerced_for_integer_params() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let invoke = || protocol.invoke("transfer", Some("preprod")).unwrap();
+
+        let request = invoke()
+            .with_arg("quantity", json!(" 100000000"))
+            .into_resolve_request()
+            .unwrap();
+
+        assert_eq!(request.args["quantity"], json!(100000000));
+
+        let err = invoke()
+            .with_arg("quantity", json!("ten"))
+            .into_resolve_request()
+            .unwrap_err();
+
+        assert!(matches!(err, Error::InvalidArg { key, .. } if key == "quantity"));
+    }
+
+    #[test]
+    fn boolean_strings_are_coerced_for_boolean_params() {
+        let protocol = protocol_with_params(json!({
+            "type": "object",
+            "properties": { "flag": { "type": "boolean" } }
+        }))
+        .build()
+        .unwrap();
+
+        let invoke = || protocol.invoke("tx", None).unwrap();
+
+        for (raw, expected) in [
+            ("TRUE", true),
+            ("false", false),
+            ("1", true),
+            (" 0 ", false),
+        ] {
+            let request = invoke()
+                .with_arg("flag", json!(raw))
+                .into_resolve_request()
+                .unwrap();
+
+            assert_eq!(request.args["flag"], json!(expected));
+        }
+
+        let err = invoke()
+            .with_arg("flag", json!("yes"))
+            .into_resolve_request()
+            .unwrap_err();
+
+        assert!(matches!(err, Error::InvalidArg { key, .. } if key == "flag"));
+    }
+
+    #[test]
+    fn narrow_returns_remaining_params() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let invoke = protocol.invoke("transfer", None).unwrap();
+        let (invoke, before) = invoke.narrow();
+
+        let (_, after) = invoke.with_arg("quantity", json!(1)).narrow();
+
+        assert!(before.iter().any(|(k, _)| k == "quantity"));
+        assert!(!after.iter().any(|(k, _)| k == "quantity"));
+        assert_eq!(after.len(), before.len() - 1);
+    }
+
+    #[test]
+    fn missing_params_are_filled_from_callback() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let mut invoke = protocol
+            .invoke("transfer", None)
+            .unwrap()
+            .with_arg("quantity", json!(7));
+
+        let mut asked = Vec::new();
+        invoke.resolve_missing_with(|name, ty| {
+            asked.push(name.to_string());
+            matches!(ty, ParamType::Address).then(|| json!(TESTNET_ADDRESS))
+        });
+
+        assert!(!asked.contains(&"quantity".to_string()));
+        assert_eq!(invoke.args["quantity"], json!(7));
+        assert_eq!(invoke.args["sender"], json!(TESTNET_ADDRESS));
+        assert!(invoke.explicit_args.contains("sender"));
+    }
+
+    #[test]
+    fn resolve_errors_map_to_params() {
+        use crate::trp::{InputNotResolvedDiagnostic, MissingTxArgDiagnostic};
+
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let invoke = protocol.invoke("transfer", None).unwrap();
+
+        let missing = crate::trp::Error::MissingTxArg(MissingTxArgDiagnostic {
+            key: "Quantity".to_string(),
+            arg_type: "Int".to_string(),
+        });
+        let (param, message) = invoke.field_error(&missing).unwrap();
+        assert_eq!(param, "quantity");
+        assert!(message.contains("missing argument"));
+
+        let diagnostic: InputNotResolvedDiagnostic = serde_json::from_value(json!({
+            "name": "source",
+            "query": {
+                "address": TESTNET_ADDRESS,
+                "collateral": false,
+                "minAmount": {},
+                "refs": [],
+                "supportMany": false
+            },
+            "search_space": { "matched": [] }
+        }))
+        .unwrap();
+        let unresolved = crate::trp::Error::InputNotResolved(Box::new(diagnostic));
+        let (param, _) = invoke.field_error(&unresolved).unwrap();
+        assert_eq!(param, "sender");
+
+        let other = crate::trp::Error::UnknownError("boom".to_string());
+        assert_eq!(invoke.field_error(&other), None);
+    }
+
+    #[test]
+    fn args_are_matched_to_mixed_case_params() {
+        use crate::trp::{InputNotResolvedDiagnostic, MissingTxArgDiagnostic};
+
+        // an `ExpectInput` for "source", at `userParty` holding `userAmount`
+        let tir = hex_tir(
+            "a16b457870656374496e7075748266736f75726365a26761646472657373a1694576616c\
+             506172616da16b45787065637456616c7565826975736572506172747967416464726573\
+             736a6d696e5f616d6f756e74a1694576616c506172616da16b45787065637456616c7565\
+             826a75736572416d6f756e7463496e74",
+        );
+
+        let protocol = Protocol::builder()
+            .tii_version("v1beta0")
+            .name("mixed")
+            .transaction(
+                "tx",
+                tir,
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "userParty": { "type": "string" },
+                        "userAmount": { "type": "integer" }
+                    }
+                }),
+            )
+            .build()
+            .unwrap();
+
+        let missing = crate::trp::Error::MissingTxArg(MissingTxArgDiagnostic {
+            key: "userAmount".to_string(),
+            arg_type: "Int".to_string(),
+        });
+
+        let diagnostic: InputNotResolvedDiagnostic = serde_json::from_value(json!({
+            "name": "source",
+            "query": {
+                "address": TESTNET_ADDRESS,
+                "collateral": false,
+                "minAmount": {},
+                "refs": [],
+                "supportMany": false
+            },
+            "search_space": { "matched": [] }
+        }))
+        .unwrap();
+        let unresolved = crate::trp::Error::InputNotResolved(Box::new(diagnostic));
+
+        for (policy, amount) in [
+            (CasePolicy::MatchDeclared, "useramount"),
+            (CasePolicy::Preserve, "UserAmount"),
+        ] {
+            let invoke = protocol
+                .invoke("tx", None)
+                .unwrap()
+                .with_case_policy(policy)
+                .with_arg("userParty", json!(TESTNET_ADDRESS))
+                .with_arg(amount, json!(7));
+
+            let queries = invoke.input_queries().unwrap();
+            assert_eq!(queries[0].address, Some(json!(TESTNET_ADDRESS)));
+            assert_eq!(queries[0].min_amount, Some(json!(7)));
+
+            let (param, _) = invoke.field_error(&missing).unwrap();
+            assert_eq!(param, "userAmount");
+
+            let (param, _) = invoke.field_error(&unresolved).unwrap();
+            assert_eq!(param, "userParty");
+        }
+    }
+
+    #[test]
+    fn invocation_links_round_trip() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let invoke = protocol
+            .invoke("transfer", Some("preprod"))
+            .unwrap()
+            .with_arg("quantity", json!(10))
+            .with_arg("sender", json!(TESTNET_ADDRESS));
+
+        let link = invoke.to_link();
+        assert!(link
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+        let opened = protocol.from_link(&link).unwrap();
+        assert_eq!(opened.args, invoke.args);
+        assert_eq!(opened.explicit_args, invoke.explicit_args);
+        assert_eq!(opened.profile.as_deref(), Some("preprod"));
+        assert_eq!(opened.to_link(), link);
+
+        assert!(matches!(
+            protocol.from_link("not a link"),
+            Err(Error::InvalidLink(_))
+        ));
+    }
+
+    struct TestWallet;
+
+    impl Wallet for TestWallet {
+        fn change_address(&self) -> String {
+            TESTNET_ADDRESS.to_string()
+        }
+
+        fn utxos(&self) -> Vec<crate::trp::TxInputRef> {
+            vec![crate::trp::TxInputRef {
+                hash: "aa".repeat(32),
+                index: 0,
+            }]
+        }
+
+        fn sign(
+            &self,
+            _: &crate::trp::TxEnvelope,
+        ) -> Result<Vec<crate::trp::TxWitness>, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(vec![])
+        }
+
+        fn address_for(&self, party: &str) -> Option<String> {
+            (party == "sender").then(|| self.change_address())
+        }
+
+        fn collateral(&self) -> Option<crate::trp::TxInputRef> {
+            self.utxos().into_iter().next()
+        }
+    }
+
+    #[test]
+    fn bind_wallet_fills_missing_parties() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let mut invoke = protocol
+            .invoke("transfer", None)
+            .unwrap()
+            .with_arg("receiver", json!(MAINNET_ADDRESS))
+            .with_arg("quantity", json!(100));
+
+        invoke.bind_wallet(&TestWallet);
+
+        let args = invoke.effective_args();
+        assert_eq!(args["sender"], json!(TESTNET_ADDRESS));
+        assert_eq!(args["receiver"], json!(MAINNET_ADDRESS));
+        assert_eq!(args["quantity"], json!(100));
+
+        // parties the wallet doesn't act as stay unset
+        assert!(!args.contains_key("middleman"));
+    }
+
+    #[test]
+    fn bind_wallet_offers_collateral() {
+        let mut invoke = protocol_with_params(json!({
+            "type": "object",
+            "properties": {
+                "collateral": { "$ref": "https://tx3.land/specs/v1beta0/core#UtxoRef" }
+            }
+        }))
+        .build()
+        .unwrap()
+        .invoke("tx", None)
+        .unwrap();
+
+        invoke.bind_wallet(&TestWallet);
+
+        assert_eq!(
+            invoke.effective_args()["collateral"],
+            json!(format!("{}#0", "aa".repeat(32)))
+        );
+    }
+
+    #[test]
+    fn profile_warnings_name_unfilled_parties() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let invoke = protocol.invoke("transfer", None).unwrap();
+        assert!(invoke.profile_warnings().is_empty());
+
+        let invoke = protocol
+            .invoke("transfer", Some("preprod"))
+            .unwrap()
+            .with_arg("sender", json!(TESTNET_ADDRESS));
+
+        let warnings = invoke.profile_warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("`middleman`"));
+        assert!(warnings[1].contains("`receiver`"));
+        assert!(warnings.iter().all(|x| x.contains("`preprod`")));
+    }
+
+    #[test]
+    fn effective_args_show_merged_values() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let invoke = protocol
+            .invoke("transfer", Some("preprod"))
+            .unwrap()
+            .with_arg("quantity", json!("12"));
+
+        assert_eq!(invoke.effective_args()["tax"], json!(5000000));
+        assert_eq!(invoke.effective_args()["quantity"], json!("12"));
+
+        let request = invoke.effective_resolve_params().unwrap();
+        assert_eq!(request.args["quantity"], json!(12));
+        assert_eq!(request.args["tax"], json!(5000000));
+
+        // Still usable afterwards
+        assert!(invoke.into_resolve_request().is_ok());
+    }
+
+    #[test]
+    fn cbor_arg_bundles_are_decoded() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let mut invoke = protocol.invoke("transfer", None).unwrap();
+
+        // {"Quantity": 5, "datum": h'd87980'}
+        let bundle = hex::decode("a2685175616e746974790565646174756d43d87980").unwrap();
+        invoke.set_args_cbor(&bundle).unwrap();
+
+        assert_eq!(invoke.args["quantity"], json!(5));
+        assert_eq!(invoke.args["datum"], json!("d87980"));
+        assert!(invoke.explicit_args.contains("quantity"));
+
+        let err = invoke.set_args_cbor(&hex::decode("8105").unwrap());
+        assert!(matches!(err, Err(Error::InvalidCborArgs(_))));
+
+        let err = invoke.set_args_cbor(&hex::decode("a1616101ff").unwrap());
+        assert!(matches!(err, Err(Error::InvalidCborArgs(_))));
+    }
+
+    #[test]
+    fn cbor_tags_survive_decoding() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let mut invoke = protocol.invoke("transfer", None).unwrap();
+
+        // {"a": 2(h'05'), "b": 2(h'010000000000000000'), "c": 3(h'00'), "d": 121([])}
+        let bundle = hex::decode(concat!(
+            "a4",
+            "6161c24105",
+            "6162c249010000000000000000",
+            "6163c34100",
+            "6164d87980",
+        ))
+        .unwrap();
+        invoke.set_args_cbor(&bundle).unwrap();
+
+        assert_eq!(invoke.args["a"], json!(5));
+        assert_eq!(invoke.args["b"], json!("18446744073709551616"));
+        assert_eq!(invoke.args["c"], json!(-1));
+        assert_eq!(invoke.args["d"], json!({ "tag": 121, "value": [] }));
+    }
+
+    #[test]
+    fn env_vars_bind_args_by_prefix() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        std::env::set_var("TX3_TEST_BIND_QUANTITY", "42");
+        std::env::set_var("TX3_TEST_BIND_SENDER", TESTNET_ADDRESS);
+        std::env::set_var("TX3_TEST_BIND_UNRELATED", "ignored");
+
+        let mut invoke = protocol.invoke("transfer", None).unwrap();
+        invoke.bind_env("TX3_TEST_BIND_").unwrap();
+
+        assert_eq!(invoke.args["quantity"], json!(42));
+        assert_eq!(invoke.args["sender"], json!(TESTNET_ADDRESS));
+        assert!(!invoke.args.contains_key("unrelated"));
+
+        std::env::set_var("TX3_TEST_BAD_QUANTITY", "lots");
+
+        let mut invoke = protocol.invoke("transfer", None).unwrap();
+        let err = invoke.bind_env("TX3_TEST_BAD_").unwrap_err();
+        assert!(matches!(err, Error::InvalidArg { key, .. } if key == "quantity"));
+        assert!(!invoke.args.contains_key("quantity"));
+    }
+
+    #[test]
+    fn input_queries_are_read_from_the_tir() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let invoke = protocol
+            .invoke("transfer", None)
+            .unwrap()
+            .with_arg("sender", json!(TESTNET_ADDRESS));
+
+        let queries = invoke.input_queries().unwrap();
+        assert_eq!(queries.len(), 1);
+
+        let source = &queries[0];
+        assert_eq!(source.name, "source");
+        assert_eq!(source.address, Some(json!(TESTNET_ADDRESS)));
+        assert!(source.min_amount.is_some());
+        assert!(source.r#ref.is_none());
+        assert!(!source.many && !source.collateral);
+    }
+
+    #[test]
+    fn tir_params_are_read_from_the_tir() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let invoke = protocol.invoke("transfer", None).unwrap();
+
+        let mut params = invoke.tir_params().unwrap();
+        params.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let names: Vec<_> = params.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["middleman", "quantity", "receiver", "sender", "tax"]
+        );
+        assert_eq!(params[1].ty, json!("Int"));
+        assert_eq!(params[3].ty, json!("Address"));
+    }
+
+    #[test]
+    fn check_tir_params_finds_drift() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+        assert!(protocol.check_tir_params().is_ok());
+
+        let drifted = drifted_transfer(json!({
+            "type": "object",
+            "properties": { "memo": { "type": "string" } }
+        }));
+
+        let errors = drifted.check_tir_params().unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(&errors[0], Error::UnusedParam { param, .. } if param == "memo"));
+        assert!(matches!(&errors[1], Error::UndeclaredParam { param, .. } if param == "quantity"));
+    }
+
+    #[test]
+    fn default_profile_applies_when_none_is_selected() {
+        let mut json: Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+        json["tii"]["defaultProfile"] = json!("preprod");
+
+        let protocol = Protocol::from_json(json.clone()).unwrap();
+        assert_eq!(protocol.default_profile(), Some("preprod"));
+
+        let invoke = protocol.invoke("transfer", None).unwrap();
+        assert_eq!(invoke.args["tax"], json!(5000000));
+
+        let bare = protocol.invoke_without_profile("transfer").unwrap();
+        assert!(!bare.args.contains_key("tax"));
+
+        let reopened = protocol.from_link(&bare.to_link()).unwrap();
+        assert!(!reopened.args.contains_key("tax"));
+
+        // tx discovery doesn't apply the default either
+        let mut args: ArgMap = ["sender", "receiver", "middleman"]
+            .into_iter()
+            .map(|x| (x.to_string(), json!(TESTNET_ADDRESS)))
+            .collect();
+        args.insert("quantity".to_string(), json!(1));
+        assert!(protocol.candidate_txs(&args).is_empty());
+
+        args.insert("tax".to_string(), json!(1));
+        assert_eq!(protocol.candidate_txs(&args), vec!["transfer"]);
+
+        json["default_profile"] = json!("missing");
+        assert!(matches!(
+            Protocol::from_json(json),
+            Err(Error::UnknownProfile(x)) if x == "missing"
+        ));
+    }
+
+    #[test]
+    fn check_tir_params_counts_params() {
+        let drifted = drifted_transfer(json!({
+            "type": "object",
+            "properties": {
+                "quantity": { "type": "integer" },
+                "memo": { "type": "string" }
+            }
+        }));
+
+        // drift is reported by the check, it doesn't block invoking
+        assert!(drifted.invoke("transfer", None).is_ok());
+
+        let errors = drifted.check_tir_params().unwrap_err();
+        match errors.last() {
+            Some(Error::ParamCountMismatch { tx, tir, schema }) => {
+                assert_eq!(tx, "transfer");
+                assert_eq!(*tir, 1);
+                assert_eq!(*schema, 2);
+            }
+            other => panic!("expected param count mismatch, got {other:?}"),
+        }
+
+        let mut cased = drifted.clone();
+        cased.spec.transactions.get_mut("transfer").unwrap().params = json!({
+            "type": "object",
+            "properties": { "Quantity": { "type": "integer" } }
+        });
+        assert!(cased.check_tir_params().is_ok());
+    }
+
+    #[test]
+    fn from_reader_enforces_the_size_limit() {
+        let code = include_str!("../../tests/fixtures/transfer.tii");
+
+        let protocol = Protocol::from_reader(code.as_bytes(), code.len()).unwrap();
+        assert!(protocol.txs().contains_key("transfer"));
+
+        let err = Protocol::from_reader(code.as_bytes(), code.len() - 1).unwrap_err();
+        assert!(matches!(err, Error::FileTooLarge { limit } if limit == code.len() - 1));
+
+        // the cut lands inside the two-byte "é"
+        let err = Protocol::from_reader("{\"é\": 1}".as_bytes(), 2).unwrap_err();
+        assert!(matches!(err, Error::FileTooLarge { limit: 2 }));
+
+        let err = Protocol::from_reader(&[0xff, 0xfe][..], 16).unwrap_err();
+        assert!(matches!(err, Error::IoError(_)));
+    }
+
+    #[tokio::test]
+    async fn from_async_reader_parses_the_buffered_tii() {
+        let code = include_str!("../../tests/fixtures/transfer.tii");
+
+        let protocol = Protocol::from_async_reader(code.as_bytes(), code.len())
+            .await
+            .unwrap();
+        assert!(protocol.txs().contains_key("transfer"));
+
+        let err = Protocol::from_async_reader(code.as_bytes(), code.len() - 1)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::FileTooLarge { limit } if limit == code.len() - 1));
+
+        let err = Protocol::from_async_reader(&[0xff, 0xfe][..], 16)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::IoError(_)));
+    }
+
+    #[test]
+    fn case_policy_controls_arg_names() {
+        let protocol = protocol_with_params(json!({
+            "type": "object",
+            "properties": { "userAmount": { "type": "integer" } }
+        }))
+        .build()
+        .unwrap();
+
+        let invoke = protocol
+            .invoke("tx", None)
+            .unwrap()
+            .with_arg("USERAMOUNT", json!(1));
+        assert!(invoke.effective_args().contains_key("useramount"));
+
+        let mut invoke = protocol
+            .clone()
+            .case_policy(CasePolicy::MatchDeclared)
+            .invoke("tx", None)
+            .unwrap()
+            .with_arg("USERAMOUNT", json!(1))
+            .with_arg("Other", json!(2));
+        assert!(invoke.effective_args().contains_key("userAmount"));
+        assert!(invoke.effective_args().contains_key("Other"));
+        assert_eq!(invoke.unspecified_params().count(), 0);
+
+        let invoke = protocol
+            .invoke("tx", None)
+            .unwrap()
+            .with_case_policy(CasePolicy::Preserve)
+            .with_arg("UserAmount", json!(1));
+        assert!(invoke.effective_args().contains_key("UserAmount"));
+    }
+
+    #[test]
+    fn metadata_is_checked_and_sent() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+        let invoke = || protocol.invoke("transfer", None).unwrap();
+
+        let request = invoke()
+            .with_metadata(674, json!({ "msg": ["order #42", 7] }))
+            .unwrap()
+            .into_resolve_request()
+            .unwrap();
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            body["metadata"],
+            json!({ "674": { "msg": ["order #42", 7] } })
+        );
+
+        let long = "x".repeat(65);
+        for value in [json!(long), json!({ long: 1 }), json!([1.5]), json!(true)] {
+            assert!(matches!(
+                invoke().with_metadata(1, value),
+                Err(Error::InvalidMetadata { label: 1, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn arg_report_splits_params_and_args() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let report = protocol
+            .invoke("transfer", Some("preprod"))
+            .unwrap()
+            .with_arg("quantity", json!(100))
+            .with_arg("memo", json!("hi"))
+            .arg_report();
+
+        let satisfied: Vec<_> = report.satisfied.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(satisfied, vec!["quantity", "tax"]);
+
+        let missing: Vec<_> = report.missing.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(missing, vec!["middleman", "receiver", "sender"]);
+
+        assert_eq!(report.extra, vec!["memo"]);
+        assert!(!report.is_complete());
+    }
+
+    #[test]
+    fn with_parties_sets_every_address() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+        let invoke = || protocol.invoke("transfer", None).unwrap();
+
+        let parties = |entries: &[(&str, &str)]| {
+            entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+
+        let bound = invoke()
+            .with_parties(parties(&[
+                ("Sender", TESTNET_ADDRESS),
+                ("receiver", TESTNET_ADDRESS),
+            ]))
+            .unwrap();
+        assert_eq!(bound.effective_args()["sender"], json!(TESTNET_ADDRESS));
+        assert_eq!(bound.effective_args()["receiver"], json!(TESTNET_ADDRESS));
+
+        let err = invoke()
+            .with_parties(parties(&[("quantity", TESTNET_ADDRESS)]))
+            .unwrap_err();
+        assert!(matches!(err, Error::UnknownParty(name) if name == "quantity"));
+
+        let err = invoke()
+            .with_parties(parties(&[("sender", "not-an-address")]))
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidArg { key, .. } if key == "sender"));
+    }
+
+    #[test]
+    fn profile_env_fills_tx_params_without_env_schema() {
+        let protocol = protocol_with_params(json!({
+            "type": "object",
+            "properties": { "fee": { "type": "integer" } }
+        }))
+        .profile(
+            "preprod",
+            spec::Profile {
+                environment: json!({ "fee": 7, "stray": 1 }),
+                ..Default::default()
+            },
+        )
+        .build()
+        .unwrap();
+        assert!(protocol.spec.environment.is_none());
+
+        let report = protocol.invoke("tx", Some("preprod")).unwrap().arg_report();
+        assert_eq!(report.satisfied, vec![("fee".to_string(), json!(7))]);
+        assert!(report.missing.is_empty());
+        assert_eq!(report.extra, vec!["stray"]);
+
+        let err = protocol
+            .strict_profiles(true)
+            .invoke("tx", Some("preprod"))
+            .unwrap_err();
+        assert!(matches!(err, Error::UnknownProfileArg { key, .. } if key == "stray"));
+    }
+
+    #[test]
+    fn diff_args_lists_changed_keys() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let before = protocol
+            .invoke("transfer", Some("preprod"))
+            .unwrap()
+            .with_arg("quantity", json!(100))
+            .with_arg("sender", json!(TESTNET_ADDRESS));
+
+        let after = protocol
+            .invoke("transfer", None)
+            .unwrap()
+            .with_arg("quantity", json!(200))
+            .with_arg("sender", json!(TESTNET_ADDRESS));
+
+        assert_eq!(
+            before.diff_args(&after),
+            vec![
+                ("quantity".to_string(), Some(json!(100)), Some(json!(200))),
+                ("tax".to_string(), Some(json!(5000000)), None),
+            ]
+        );
+        assert!(before.diff_args(&before).is_empty());
+    }
+
+    #[test]
+    fn fee_input_is_sent_as_a_hint() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let request = protocol
+            .invoke("transfer", None)
+            .unwrap()
+            .into_resolve_request()
+            .unwrap();
+        assert!(serde_json::to_value(&request)
+            .unwrap()
+            .get("feeInput")
+            .is_none());
+
+        let utxo = format!("{}#1", "ab".repeat(32));
+        let request = protocol
+            .invoke("transfer", None)
+            .unwrap()
+            .with_fee_input(utxo.clone())
+            .into_resolve_request()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap()["feeInput"],
+            json!(utxo)
+        );
+    }
+
+    #[test]
+    fn describe_marks_missing_params() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let invoke = protocol
+            .invoke("transfer", Some("preprod"))
+            .unwrap()
+            .with_arg("quantity", json!(100));
+
+        let text = invoke.describe();
+
+        assert!(text.starts_with("tx: transfer (tir v1beta0)\n"));
+        assert!(text.contains("  quantity: Integer = 100\n"));
+        assert!(text.contains("  tax: Integer = 5000000\n"));
+        assert!(text.contains("  receiver: Address = MISSING\n"));
+    }
+
+    #[test]
+    fn rejects_unsupported_tii_versions() {
+        let mut json: Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let protocol = Protocol::from_json(json.clone()).unwrap();
+        assert_eq!(protocol.tii_version(), "v1beta0");
+
+        json["tii"]["version"] = json!("v2");
+        let err = Protocol::from_json(json).unwrap_err();
+        assert!(matches!(err, Error::UnsupportedTiiVersion(v) if v == "v2"));
+    }
+
+    #[test]
+    fn ordered_txs_keep_declaration_order() {
+        let tx = r#"{ "tir": { "content": "00", "encoding": "hex", "version": "v1beta0" }, "params": {} }"#;
+        let code = format!(
+            r#"{{
+                "tii": {{ "version": "v1beta0" }},
+                "protocol": {{ "name": "p", "version": "0.1.0", "scope": "" }},
+                "transactions": {{ "zeta": {tx}, "alpha": {tx}, "mid": {tx} }},
+                "parties": {{}}
+            }}"#
+        );
+
+        let protocol = Protocol::from_string(code.clone()).unwrap();
+        let names: Vec<_> = protocol.ordered_txs().into_iter().map(|(x, _)| x).collect();
+        assert_eq!(names, vec!["zeta", "alpha", "mid"]);
+
+        // A parsed `Value` has no order left; names are sorted instead.
+        let protocol = Protocol::from_json(serde_json::from_str(&code).unwrap()).unwrap();
+        let names: Vec<_> = protocol.ordered_txs().into_iter().map(|(x, _)| x).collect();
+        assert_eq!(names, vec!["alpha", "mid", "zeta"]);
+    }
+
+    #[test]
+    fn tx_by_index_follows_declaration_order() {
+        let tx = r#"{ "tir": { "content": "00", "encoding": "hex", "version": "v1beta0" }, "params": {} }"#;
+        let code = format!(
+            r#"{{
+                "tii": {{ "version": "v1beta0" }},
+                "protocol": {{ "name": "p", "version": "0.1.0", "scope": "" }},
+                "transactions": {{ "zeta": {tx}, "alpha": {tx} }}
+            }}"#
+        );
+
+        let protocol = Protocol::from_string(code).unwrap();
+        assert_eq!(protocol.tx_by_index(0).map(|(x, _)| x), Some("zeta"));
+        assert_eq!(protocol.tx_by_index(1).map(|(x, _)| x), Some("alpha"));
+        assert!(protocol.tx_by_index(2).is_none());
+    }
+
+    #[test]
+    fn candidate_txs_need_every_param_covered() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        let mut args: ArgMap = protocol
+            .invoke("transfer", None)
+            .unwrap()
+            .params()
+            .keys()
+            .map(|k| (k.to_uppercase(), json!(1)))
+            .collect();
+
+        assert_eq!(protocol.candidate_txs(&args), vec!["transfer".to_string()]);
+
+        args.remove("QUANTITY");
+        assert!(protocol.candidate_txs(&args).is_empty());
+    }
+
+    #[test]
+    fn can_invoke_flags_irrelevant_profiles() {
+        let protocol =
+            Protocol::from_static(include_str!("../../tests/fixtures/transfer.tii")).unwrap();
+
+        assert!(protocol.can_invoke("transfer", "preprod").is_ok());
+
+        let err = protocol.can_invoke("transfer", "local").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::IrrelevantProfile { tx, profile } if tx == "transfer" && profile == "local"
+        ));
+
+        assert!(matches!(
+            protocol.can_invoke("transfer", "nope"),
+            Err(Error::UnknownProfile(_))
+        ));
+    }
+
+    #[test]
+    fn from_string_rejects_duplicate_txs() {
+        let code = r#"{
+            "tii": { "version": "v1beta0" },
+            "protocol": { "name": "p", "version": "0.1.0", "scope": "" },
+            "transactions": {
+                "transfer": { "tir": { "content": "00", "encoding": "hex", "version": "v1beta0" }, "params": {} },
+                "transfer": { "tir": { "content": "01", "encoding": "hex", "version": "v1beta0" }, "params": {} }
+            },
+            "parties": {}
+        }"#;
+
+        let err = Protocol::from_string(code.to_string()).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::DuplicateKey { section, key } if section == "transactions" && key == "transfer"
+        ));
     }
 
     #[test]
@@ -4,30 +4,284 @@
 //! (and an optional environment schema). This module turns those schema nodes —
 //! every shape `tx3c` can emit, see the SDK spec's `api-surface/args.md` — into
 //! the [`ParamType`] model the rest of the SDK works with. Interpretation never
-//! fails: any shape it does not recognize becomes [`ParamType::Unknown`].
+//! fails on shape: any shape it does not recognize becomes [`ParamType::Unknown`].
+//! The walk is depth-bounded though, so hostile or cyclic schemas can't
+//! exhaust the stack.
 
-use serde_json::Value;
+use serde_json::{json, Map, Value};
 use std::collections::{BTreeMap, HashMap};
 
+use crate::tii::Error;
+
+/// Base URL for the canonical `$ref` form of the built-in core types.
+const CORE_DEFS_URL: &str = "https://tx3.land/specs/v1beta0/tii#/$defs";
+
 /// Map of parameter names to their types.
 ///
 /// Used to represent the complete set of parameters required for a transaction.
 pub type ParamMap = HashMap<String, ParamType>;
 
-/// Builds a parameter-type map from a JSON schema's `properties`. Never fails:
-/// unrecognized property schemas yield [`ParamType::Unknown`]. `components` is the
-/// TII's `components.schemas` table, used to resolve `#/components/schemas/<Name>`
+/// Default bound on schema nesting, see [`ParamType::from_json_schema_with_limit`].
+pub const DEFAULT_MAX_SCHEMA_DEPTH: usize = 64;
+
+/// Builds a parameter-type map from a JSON schema's `properties`. Unrecognized
+/// property schemas yield [`ParamType::Unknown`]; the only failure is a schema
+/// nested deeper than `max_depth`. `components` is the TII's
+/// `components.schemas` table, used to resolve `#/components/schemas/<Name>`
 /// refs to user-defined record / variant types.
-pub(super) fn params_from_schema(schema: &Value, components: &HashMap<String, Value>) -> ParamMap {
+pub(super) fn params_from_schema(
+    schema: &Value,
+    components: &HashMap<String, Value>,
+    max_depth: usize,
+) -> Result<ParamMap, Error> {
     let mut params = ParamMap::new();
 
     if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
         for (key, value) in properties {
-            params.insert(key.clone(), ParamType::from_json_schema(value, components));
+            let ty = ParamType::from_json_schema_with_limit(value, components, max_depth)?;
+            params.insert(key.clone(), ty);
+        }
+    }
+
+    Ok(params)
+}
+
+/// Collects the `default` values declared on a JSON schema's `properties`,
+/// keyed by property name. Properties without a `default` are skipped.
+pub(super) fn defaults_from_schema(schema: &Value) -> Map<String, Value> {
+    let mut defaults = Map::new();
+
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        for (key, value) in properties {
+            if let Some(default) = value.get("default") {
+                defaults.insert(key.clone(), default.clone());
+            }
+        }
+    }
+
+    defaults
+}
+
+/// Collects the `examples` declared on a JSON schema's `properties`, keyed by
+/// property name. A single `example` (the OpenAPI spelling) counts as a
+/// one-item list; properties with neither are skipped.
+pub(super) fn examples_from_schema(schema: &Value) -> HashMap<String, Vec<Value>> {
+    let mut examples = HashMap::new();
+
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        for (key, value) in properties {
+            let found = match (value.get("examples"), value.get("example")) {
+                (Some(Value::Array(list)), _) => list.clone(),
+                (_, Some(example)) => vec![example.clone()],
+                _ => continue,
+            };
+
+            if !found.is_empty() {
+                examples.insert(key.clone(), found);
+            }
+        }
+    }
+
+    examples
+}
+
+/// Value constraints a param schema declares on top of its type.
+///
+/// Read from the JSON schema keywords `minimum` / `maximum` (integers) and
+/// `minLength` / `maxLength` / `pattern` (strings), plus the `x-address-kind`
+/// extension on addresses. Fractional bounds on an integer are rounded
+/// inwards.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParamConstraints {
+    /// Smallest allowed integer value, inclusive.
+    pub minimum: Option<i128>,
+    /// Largest allowed integer value, inclusive.
+    pub maximum: Option<i128>,
+    /// Smallest allowed string length, in characters.
+    pub min_length: Option<u64>,
+    /// Largest allowed string length, in characters.
+    pub max_length: Option<u64>,
+    /// Regular expression (ECMA-262 dialect) a string value must match.
+    pub pattern: Option<String>,
+    /// Kind of address an `Address` value must be.
+    pub address_kind: Option<AddressKind>,
+}
+
+impl ParamConstraints {
+    /// Reads the constraints of the property `key`.
+    ///
+    /// Fails on an `x-address-kind` that names no [`AddressKind`], rather
+    /// than leave the param unconstrained.
+    fn from_schema(key: &str, schema: &Value) -> Result<Self, Error> {
+        let address_kind = match schema.get("x-address-kind") {
+            None => None,
+            Some(kind) => {
+                let found = kind.as_str().and_then(AddressKind::from_name);
+
+                Some(found.ok_or_else(|| Error::UnknownAddressKind {
+                    key: key.to_string(),
+                    kind: kind.to_string(),
+                })?)
+            }
+        };
+
+        let bound = |key: &str, round: fn(f64) -> f64| {
+            let number = schema.get(key)?.as_number()?;
+
+            number
+                .as_i64()
+                .map(i128::from)
+                .or_else(|| number.as_u64().map(i128::from))
+                .or_else(|| number.as_f64().map(|x| round(x) as i128))
+        };
+
+        Ok(ParamConstraints {
+            minimum: bound("minimum", f64::ceil),
+            maximum: bound("maximum", f64::floor),
+            min_length: schema.get("minLength").and_then(Value::as_u64),
+            max_length: schema.get("maxLength").and_then(Value::as_u64),
+            pattern: schema
+                .get("pattern")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            address_kind,
+        })
+    }
+
+    /// Whether no constraint is set.
+    pub fn is_empty(&self) -> bool {
+        *self == ParamConstraints::default()
+    }
+
+    /// Checks a value against the numeric and length constraints.
+    ///
+    /// `pattern` is not checked, as the SDK carries no regex engine; it is
+    /// exposed for callers to enforce. Values of other shapes pass.
+    pub(super) fn check(&self, value: &Value) -> Result<(), String> {
+        if let Some(x) = value
+            .as_i64()
+            .map(i128::from)
+            .or(value.as_u64().map(i128::from))
+        {
+            if let Some(min) = self.minimum.filter(|min| x < *min) {
+                return Err(format!("{x} is less than the minimum of {min}"));
+            }
+
+            if let Some(max) = self.maximum.filter(|max| x > *max) {
+                return Err(format!("{x} is greater than the maximum of {max}"));
+            }
+        }
+
+        if let Some(text) = value.as_str() {
+            let len = text.chars().count() as u64;
+
+            if let Some(min) = self.min_length.filter(|min| len < *min) {
+                return Err(format!("length {len} is shorter than the minimum of {min}"));
+            }
+
+            if let Some(max) = self.max_length.filter(|max| len > *max) {
+                return Err(format!("length {len} is longer than the maximum of {max}"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A kind of Cardano address, as required by the `x-address-kind` schema
+/// extension.
+///
+/// An unknown kind name in a schema fails the invocation with
+/// `Error::UnknownAddressKind`, and [`crate::tii::Protocol::lint`] reports
+/// it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddressKind {
+    /// A Shelley address with a stake part (`"base"`).
+    Base,
+    /// A Shelley address without a stake part (`"enterprise"`).
+    Enterprise,
+    /// A Shelley address whose stake part is a pointer (`"pointer"`).
+    Pointer,
+    /// A reward address (`"stake"`).
+    Stake,
+    /// A legacy Byron address (`"byron"`).
+    Byron,
+    /// A Shelley or reward address whose payment part, or stake part for a
+    /// reward address, is a script (`"script"`).
+    Script,
+}
+
+impl AddressKind {
+    pub(super) fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "base" => Some(AddressKind::Base),
+            "enterprise" => Some(AddressKind::Enterprise),
+            "pointer" => Some(AddressKind::Pointer),
+            "stake" => Some(AddressKind::Stake),
+            "byron" => Some(AddressKind::Byron),
+            "script" => Some(AddressKind::Script),
+            _ => None,
+        }
+    }
+
+    /// Whether `address` is of this kind.
+    pub(super) fn matches(&self, address: &pallas_addresses::Address) -> bool {
+        use pallas_addresses::{Address, ShelleyDelegationPart};
+
+        match (self, address) {
+            (AddressKind::Base, Address::Shelley(x)) => matches!(
+                x.delegation(),
+                ShelleyDelegationPart::Key(_) | ShelleyDelegationPart::Script(_)
+            ),
+            (AddressKind::Enterprise, Address::Shelley(x)) => {
+                matches!(x.delegation(), ShelleyDelegationPart::Null)
+            }
+            (AddressKind::Pointer, Address::Shelley(x)) => {
+                matches!(x.delegation(), ShelleyDelegationPart::Pointer(_))
+            }
+            (AddressKind::Stake, Address::Stake(_)) => true,
+            (AddressKind::Byron, Address::Byron(_)) => true,
+            (AddressKind::Script, Address::Shelley(x)) => x.payment().is_script(),
+            (AddressKind::Script, Address::Stake(x)) => x.is_script(),
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for AddressKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AddressKind::Base => "base",
+            AddressKind::Enterprise => "enterprise",
+            AddressKind::Pointer => "pointer",
+            AddressKind::Stake => "stake",
+            AddressKind::Byron => "byron",
+            AddressKind::Script => "script",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// Collects the constraints declared on a JSON schema's `properties`, keyed
+/// by property name. Properties without constraints are skipped; the only
+/// failure is an unknown `x-address-kind`.
+pub(super) fn constraints_from_schema(
+    schema: &Value,
+) -> Result<HashMap<String, ParamConstraints>, Error> {
+    let mut constraints = HashMap::new();
+
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        for (key, value) in properties {
+            let found = ParamConstraints::from_schema(key, value)?;
+
+            if !found.is_empty() {
+                constraints.insert(key.clone(), found);
+            }
         }
     }
 
-    params
+    Ok(constraints)
 }
 
 /// Type of a transaction parameter.
@@ -64,6 +318,9 @@ pub enum ParamType {
     Record(BTreeMap<String, ParamType>),
     /// User-defined tagged union (`oneOf`), externally tagged.
     Variant(Vec<VariantCase>),
+    /// Untagged union (`anyOf`, or a `oneOf` whose branches aren't all
+    /// externally tagged): a value of any of the branch types.
+    OneOf(Vec<ParamType>),
     /// A schema shape that could not be interpreted; carries the raw schema.
     Unknown(Value),
 }
@@ -77,47 +334,88 @@ pub struct VariantCase {
     pub fields: Box<ParamType>,
 }
 
-impl ParamType {
-    /// Maps a built-in core `$ref` to its kind by trailing name, so both the
-    /// canonical `…/tii#/$defs/<Name>` and legacy `…/core#<Name>` forms resolve.
-    fn core_ref_type(reference: &str) -> Option<ParamType> {
-        let name = reference.rsplit(['#', '/']).next().unwrap_or("");
-        match name {
-            "Bytes" => Some(ParamType::Bytes),
-            "Address" => Some(ParamType::Address),
-            "UtxoRef" => Some(ParamType::UtxoRef),
-            "Utxo" => Some(ParamType::Utxo),
-            "AnyAsset" => Some(ParamType::AnyAsset),
-            _ => None,
+/// Recursive interpretation of schema nodes, carrying the components table
+/// and the depth limit down the walk.
+struct SchemaWalk<'a> {
+    components: &'a HashMap<String, Value>,
+    max_depth: usize,
+}
+
+impl SchemaWalk<'_> {
+    fn param_type(&self, schema: &Value, depth: usize) -> Result<ParamType, Error> {
+        if depth > self.max_depth {
+            return Err(Error::SchemaTooDeep(self.max_depth));
         }
+
+        let Some(obj) = schema.as_object() else {
+            return Ok(ParamType::Unknown(schema.clone()));
+        };
+
+        if let Some(reference) = obj.get("$ref").and_then(Value::as_str) {
+            return self.ref_type(schema, reference, depth);
+        }
+
+        if let Some(cases) = obj.get("oneOf").and_then(Value::as_array) {
+            if cases.iter().all(is_tagged_case) {
+                return self.variant_type(cases, depth);
+            }
+
+            return self.one_of_type(cases, depth);
+        }
+
+        if let Some(branches) = obj.get("anyOf").and_then(Value::as_array) {
+            return self.one_of_type(branches, depth);
+        }
+
+        let out = match obj.get("type").and_then(Value::as_str) {
+            Some("integer") => ParamType::Integer,
+            Some("boolean") => ParamType::Boolean,
+            Some("null") => ParamType::Unit,
+            Some("array") => self.array_type(schema, depth)?,
+            Some("object") => self.object_type(schema, depth)?,
+            _ => ParamType::Unknown(schema.clone()),
+        };
+
+        Ok(out)
     }
 
     /// Resolves a `$ref` node: `#/components/schemas/<Name>` against the TII's
     /// `components` table (recursing into the resolved schema), otherwise a
     /// built-in core ref. An unresolved ref becomes [`ParamType::Unknown`].
-    fn ref_type(schema: &Value, reference: &str, components: &HashMap<String, Value>) -> ParamType {
+    fn ref_type(&self, schema: &Value, reference: &str, depth: usize) -> Result<ParamType, Error> {
         if let Some(name) = reference.strip_prefix("#/components/schemas/") {
-            return match components.get(name) {
-                Some(resolved) => Self::from_json_schema(resolved, components),
-                None => ParamType::Unknown(schema.clone()),
+            return match self.components.get(name) {
+                Some(resolved) => self.param_type(resolved, depth + 1),
+                None => Ok(ParamType::Unknown(schema.clone())),
             };
         }
 
-        Self::core_ref_type(reference).unwrap_or_else(|| ParamType::Unknown(schema.clone()))
+        Ok(ParamType::core_ref_type(reference)
+            .unwrap_or_else(|| ParamType::Unknown(schema.clone())))
     }
 
     /// Maps a `oneOf` array to a [`ParamType::Variant`] of externally-tagged cases.
-    fn variant_type(cases: &[Value], components: &HashMap<String, Value>) -> ParamType {
-        ParamType::Variant(
-            cases
-                .iter()
-                .map(|case| Self::variant_case(case, components))
-                .collect(),
-        )
+    fn variant_type(&self, cases: &[Value], depth: usize) -> Result<ParamType, Error> {
+        let cases = cases
+            .iter()
+            .map(|case| self.variant_case(case, depth + 1))
+            .collect::<Result<_, _>>()?;
+
+        Ok(ParamType::Variant(cases))
+    }
+
+    /// Maps untagged union branches to a [`ParamType::OneOf`].
+    fn one_of_type(&self, branches: &[Value], depth: usize) -> Result<ParamType, Error> {
+        let branches = branches
+            .iter()
+            .map(|branch| self.param_type(branch, depth + 1))
+            .collect::<Result<_, _>>()?;
+
+        Ok(ParamType::OneOf(branches))
     }
 
     /// Interprets one externally-tagged `oneOf` branch into a [`VariantCase`].
-    fn variant_case(case: &Value, components: &HashMap<String, Value>) -> VariantCase {
+    fn variant_case(&self, case: &Value, depth: usize) -> Result<VariantCase, Error> {
         let tag = case
             .get("required")
             .and_then(Value::as_array)
@@ -126,50 +424,115 @@ impl ParamType {
             .unwrap_or_default()
             .to_string();
 
-        let fields = case
+        let fields = match case
             .get("properties")
             .and_then(Value::as_object)
             .and_then(|props| props.get(&tag))
-            .map(|fields| Self::from_json_schema(fields, components))
-            .unwrap_or_else(|| ParamType::Unknown(case.clone()));
+        {
+            Some(fields) => self.param_type(fields, depth + 1)?,
+            None => ParamType::Unknown(case.clone()),
+        };
 
-        VariantCase {
+        Ok(VariantCase {
             tag,
             fields: Box::new(fields),
-        }
+        })
     }
 
     /// Maps an `array` schema: `prefixItems` → [`ParamType::Tuple`], `items` →
     /// [`ParamType::List`]. An array carrying neither becomes [`ParamType::Unknown`].
-    fn array_type(schema: &Value, components: &HashMap<String, Value>) -> ParamType {
+    fn array_type(&self, schema: &Value, depth: usize) -> Result<ParamType, Error> {
         if let Some(prefix) = schema.get("prefixItems").and_then(Value::as_array) {
-            ParamType::Tuple(
-                prefix
-                    .iter()
-                    .map(|el| Self::from_json_schema(el, components))
-                    .collect(),
-            )
+            let items = prefix
+                .iter()
+                .map(|el| self.param_type(el, depth + 1))
+                .collect::<Result<_, _>>()?;
+
+            Ok(ParamType::Tuple(items))
         } else if let Some(items) = schema.get("items").filter(|i| i.is_object()) {
-            ParamType::List(Box::new(Self::from_json_schema(items, components)))
+            Ok(ParamType::List(Box::new(
+                self.param_type(items, depth + 1)?,
+            )))
         } else {
-            ParamType::Unknown(schema.clone())
+            Ok(ParamType::Unknown(schema.clone()))
         }
     }
 
     /// Maps an `object` schema: `additionalProperties` → [`ParamType::Map`],
     /// `properties` → [`ParamType::Record`]. Neither present → [`ParamType::Unknown`].
-    fn object_type(schema: &Value, components: &HashMap<String, Value>) -> ParamType {
+    fn object_type(&self, schema: &Value, depth: usize) -> Result<ParamType, Error> {
         if let Some(value) = schema.get("additionalProperties").filter(|v| v.is_object()) {
-            ParamType::Map(Box::new(Self::from_json_schema(value, components)))
+            Ok(ParamType::Map(Box::new(self.param_type(value, depth + 1)?)))
         } else if let Some(props) = schema.get("properties").and_then(Value::as_object) {
-            ParamType::Record(
-                props
-                    .iter()
-                    .map(|(k, v)| (k.clone(), Self::from_json_schema(v, components)))
-                    .collect(),
-            )
+            let fields = props
+                .iter()
+                .map(|(k, v)| Ok((k.clone(), self.param_type(v, depth + 1)?)))
+                .collect::<Result<_, Error>>()?;
+
+            Ok(ParamType::Record(fields))
         } else {
-            ParamType::Unknown(schema.clone())
+            Ok(ParamType::Unknown(schema.clone()))
+        }
+    }
+}
+
+/// Whether a `oneOf` branch is an externally-tagged case: an object whose
+/// single `required` key is its payload property.
+fn is_tagged_case(case: &Value) -> bool {
+    let required = case.get("required").and_then(Value::as_array);
+
+    let Some([tag]) = required.map(Vec::as_slice) else {
+        return false;
+    };
+
+    tag.as_str().is_some_and(|tag| {
+        case.get("properties")
+            .and_then(Value::as_object)
+            .is_some_and(|props| props.contains_key(tag))
+    })
+}
+
+impl ParamType {
+    /// Short human-readable name of the type, e.g. `List<Address>`.
+    ///
+    /// Records and variants are named by shape only; their fields are not
+    /// expanded.
+    pub(super) fn label(&self) -> String {
+        let join = |items: &[ParamType]| {
+            let labels: Vec<_> = items.iter().map(ParamType::label).collect();
+            labels.join(", ")
+        };
+
+        match self {
+            ParamType::Bytes => "Bytes".to_string(),
+            ParamType::Integer => "Integer".to_string(),
+            ParamType::Boolean => "Boolean".to_string(),
+            ParamType::Unit => "Unit".to_string(),
+            ParamType::UtxoRef => "UtxoRef".to_string(),
+            ParamType::Address => "Address".to_string(),
+            ParamType::Utxo => "Utxo".to_string(),
+            ParamType::AnyAsset => "AnyAsset".to_string(),
+            ParamType::List(inner) => format!("List<{}>", inner.label()),
+            ParamType::Tuple(items) => format!("Tuple<{}>", join(items)),
+            ParamType::Map(inner) => format!("Map<{}>", inner.label()),
+            ParamType::Record(_) => "Record".to_string(),
+            ParamType::Variant(_) => "Variant".to_string(),
+            ParamType::OneOf(items) => format!("OneOf<{}>", join(items)),
+            ParamType::Unknown(_) => "Unknown".to_string(),
+        }
+    }
+
+    /// Maps a built-in core `$ref` to its kind by trailing name, so both the
+    /// canonical `…/tii#/$defs/<Name>` and legacy `…/core#<Name>` forms resolve.
+    pub(super) fn core_ref_type(reference: &str) -> Option<ParamType> {
+        let name = reference.rsplit(['#', '/']).next().unwrap_or("");
+        match name {
+            "Bytes" => Some(ParamType::Bytes),
+            "Address" => Some(ParamType::Address),
+            "UtxoRef" => Some(ParamType::UtxoRef),
+            "Utxo" => Some(ParamType::Utxo),
+            "AnyAsset" => Some(ParamType::AnyAsset),
+            _ => None,
         }
     }
 
@@ -178,7 +541,9 @@ impl ParamType {
     /// Interprets every shape `tx3c` can emit (see the SDK spec's
     /// `api-surface/args.md`). It never fails: an unrecognized shape — including a
     /// bare `string`, an unresolved object, or an unknown `$ref` — becomes
-    /// [`ParamType::Unknown`] carrying the raw schema.
+    /// [`ParamType::Unknown`] carrying the raw schema. So does a schema nested
+    /// deeper than [`DEFAULT_MAX_SCHEMA_DEPTH`] (e.g. a cyclic component ref);
+    /// use [`ParamType::from_json_schema_with_limit`] to detect that case.
     ///
     /// # Arguments
     ///
@@ -186,25 +551,209 @@ impl ParamType {
     /// * `components` - The TII's `components.schemas` table, used to resolve
     ///   `#/components/schemas/<Name>` references to user-defined types
     pub fn from_json_schema(schema: &Value, components: &HashMap<String, Value>) -> ParamType {
-        let Some(obj) = schema.as_object() else {
-            return ParamType::Unknown(schema.clone());
+        Self::from_json_schema_with_limit(schema, components, DEFAULT_MAX_SCHEMA_DEPTH)
+            .unwrap_or_else(|_| ParamType::Unknown(schema.clone()))
+    }
+
+    /// Creates a parameter type from a JSON schema node, bounding how deep the
+    /// walk may recurse.
+    ///
+    /// Each nested list item, tuple element, map value, record field, variant
+    /// case and resolved component ref counts as one level. Shapes that are
+    /// not recognized still become [`ParamType::Unknown`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::SchemaTooDeep` if the schema nests deeper than
+    /// `max_depth`, which is how cyclic component refs surface too.
+    pub fn from_json_schema_with_limit(
+        schema: &Value,
+        components: &HashMap<String, Value>,
+        max_depth: usize,
+    ) -> Result<ParamType, Error> {
+        let walk = SchemaWalk {
+            components,
+            max_depth,
         };
 
-        if let Some(reference) = obj.get("$ref").and_then(Value::as_str) {
-            return Self::ref_type(schema, reference, components);
+        walk.param_type(schema, 0)
+    }
+
+    /// Returns the fields of a user-defined [`ParamType::Record`] as owned
+    /// (name, type) pairs, in field-name order.
+    ///
+    /// Field types are already interpreted (component refs included), so a
+    /// nested record field can be expanded by calling `custom_fields` on it in
+    /// turn. Returns `None` for every other kind.
+    pub fn custom_fields(&self) -> Option<Vec<(String, ParamType)>> {
+        match self {
+            ParamType::Record(fields) => Some(
+                fields
+                    .iter()
+                    .map(|(name, ty)| (name.clone(), ty.clone()))
+                    .collect(),
+            ),
+            _ => None,
         }
+    }
 
-        if let Some(cases) = obj.get("oneOf").and_then(Value::as_array) {
-            return Self::variant_type(cases, components);
+    /// Converts this parameter type back into a JSON schema node.
+    ///
+    /// This is the inverse of [`ParamType::from_json_schema`]: core types emit
+    /// their canonical `…/tii#/$defs/<Name>` `$ref`, primitives emit their
+    /// `type`, and compound kinds are expanded inline (component refs are not
+    /// reconstructed). [`ParamType::Unknown`] returns the raw schema it carries.
+    pub fn to_json_schema(&self) -> Value {
+        let core_ref = |name: &str| json!({ "$ref": format!("{CORE_DEFS_URL}/{name}") });
+
+        match self {
+            ParamType::Bytes => core_ref("Bytes"),
+            ParamType::Integer => json!({ "type": "integer" }),
+            ParamType::Boolean => json!({ "type": "boolean" }),
+            ParamType::Unit => json!({ "type": "null" }),
+            ParamType::UtxoRef => core_ref("UtxoRef"),
+            ParamType::Address => core_ref("Address"),
+            ParamType::Utxo => core_ref("Utxo"),
+            ParamType::AnyAsset => core_ref("AnyAsset"),
+            ParamType::List(inner) => json!({
+                "type": "array",
+                "items": inner.to_json_schema(),
+            }),
+            ParamType::Tuple(items) => json!({
+                "type": "array",
+                "prefixItems": items.iter().map(ParamType::to_json_schema).collect::<Vec<_>>(),
+            }),
+            ParamType::Map(inner) => json!({
+                "type": "object",
+                "additionalProperties": inner.to_json_schema(),
+            }),
+            ParamType::Record(fields) => {
+                let properties: Map<String, Value> = fields
+                    .iter()
+                    .map(|(name, ty)| (name.clone(), ty.to_json_schema()))
+                    .collect();
+
+                json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": fields.keys().collect::<Vec<_>>(),
+                })
+            }
+            ParamType::Variant(cases) => json!({
+                "oneOf": cases
+                    .iter()
+                    .map(|case| json!({
+                        "type": "object",
+                        "additionalProperties": false,
+                        "required": [case.tag],
+                        "properties": { case.tag.clone(): case.fields.to_json_schema() },
+                    }))
+                    .collect::<Vec<_>>(),
+            }),
+            ParamType::OneOf(branches) => json!({
+                "anyOf": branches.iter().map(ParamType::to_json_schema).collect::<Vec<_>>(),
+            }),
+            ParamType::Unknown(schema) => schema.clone(),
         }
+    }
 
-        match obj.get("type").and_then(Value::as_str) {
-            Some("integer") => ParamType::Integer,
-            Some("boolean") => ParamType::Boolean,
-            Some("null") => ParamType::Unit,
-            Some("array") => Self::array_type(schema, components),
-            Some("object") => Self::object_type(schema, components),
-            _ => ParamType::Unknown(schema.clone()),
+    /// Checks that a JSON value has the shape this type is sent as.
+    ///
+    /// The check is structural and accepts what an invocation does: integers
+    /// and booleans may be JSON values or the strings they're coerced from
+    /// (`"42"`, `"true"`), core types strings or objects as appropriate,
+    /// compound types are checked element by element, and a
+    /// [`ParamType::OneOf`] accepts a value matching any of its branches.
+    /// Address encodings and hex contents are not verified, and
+    /// [`ParamType::Unknown`] accepts anything.
+    ///
+    /// # Errors
+    ///
+    /// A message describing the first mismatch found.
+    pub fn validate(&self, value: &Value) -> Result<(), String> {
+        let expect = |ok: bool, what: &str| {
+            if ok {
+                Ok(())
+            } else {
+                Err(format!("expected {what}, got `{value}`"))
+            }
+        };
+
+        match self {
+            ParamType::Integer => match value.as_str() {
+                Some(raw) => super::coerce_integer(raw).map(|_| ()),
+                None => expect(value.is_i64() || value.is_u64(), "an integer"),
+            },
+            ParamType::Boolean => match value.as_str() {
+                Some(raw) => super::coerce_boolean(raw).map(|_| ()),
+                None => expect(value.is_boolean(), "a boolean"),
+            },
+            ParamType::Unit => expect(value.is_null(), "null"),
+            ParamType::Bytes | ParamType::Address => expect(value.is_string(), "a string"),
+            ParamType::UtxoRef => expect(
+                value.as_str().is_some_and(|x| x.contains('#')),
+                "a `<hash>#<index>` UTxO ref",
+            ),
+            ParamType::Utxo | ParamType::AnyAsset => expect(value.is_object(), "an object"),
+            ParamType::List(inner) => {
+                let items = value
+                    .as_array()
+                    .ok_or(format!("expected a list, got `{value}`"))?;
+                items.iter().try_for_each(|x| inner.validate(x))
+            }
+            ParamType::Tuple(types) => {
+                let items = value
+                    .as_array()
+                    .filter(|x| x.len() == types.len())
+                    .ok_or(format!(
+                        "expected a tuple of {}, got `{value}`",
+                        types.len()
+                    ))?;
+
+                types
+                    .iter()
+                    .zip(items)
+                    .try_for_each(|(ty, x)| ty.validate(x))
+            }
+            ParamType::Map(inner) => {
+                let entries = value
+                    .as_object()
+                    .ok_or(format!("expected a map, got `{value}`"))?;
+                entries.values().try_for_each(|x| inner.validate(x))
+            }
+            ParamType::Record(fields) => {
+                let obj = value
+                    .as_object()
+                    .ok_or(format!("expected a record, got `{value}`"))?;
+
+                fields
+                    .iter()
+                    .try_for_each(|(name, ty)| match obj.get(name) {
+                        Some(x) => ty.validate(x).map_err(|e| format!("field `{name}`: {e}")),
+                        None => Err(format!("missing field `{name}`")),
+                    })
+            }
+            ParamType::Variant(cases) => {
+                let (tag, payload) = value
+                    .as_object()
+                    .filter(|x| x.len() == 1)
+                    .and_then(|x| x.iter().next())
+                    .ok_or(format!(
+                        "expected a single-key tagged object, got `{value}`"
+                    ))?;
+
+                let case = cases
+                    .iter()
+                    .find(|x| &x.tag == tag)
+                    .ok_or(format!("unknown case `{tag}`"))?;
+
+                case.fields.validate(payload)
+            }
+            ParamType::OneOf(branches) => expect(
+                branches.iter().any(|x| x.validate(value).is_ok()),
+                &format!("a value matching one of {} alternatives", branches.len()),
+            ),
+            ParamType::Unknown(_) => Ok(()),
         }
     }
 }
@@ -342,6 +891,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn maps_untagged_unions_to_one_of() {
+        let any_of = json!({
+            "anyOf": [
+                {"$ref": "https://tx3.land/specs/v1beta0/tii#/$defs/UtxoRef"},
+                {"type": "integer"}
+            ]
+        });
+
+        let ty = pt(any_of);
+        assert!(matches!(&ty, ParamType::OneOf(b) if b.len() == 2));
+        assert!(ty.validate(&json!("aa#0")).is_ok());
+        assert!(ty.validate(&json!(3)).is_ok());
+        assert!(ty.validate(&json!(true)).is_err());
+        assert_eq!(ty.label(), "OneOf<UtxoRef, Integer>");
+
+        // strings are accepted where the invocation would coerce them
+        assert!(ParamType::Integer.validate(&json!("42")).is_ok());
+        assert!(ParamType::Integer.validate(&json!("forty")).is_err());
+        assert!(ParamType::Boolean.validate(&json!("true")).is_ok());
+        assert!(ParamType::Boolean.validate(&json!("yes")).is_err());
+
+        let one_of = json!({ "oneOf": [{"type": "boolean"}, {"type": "null"}] });
+        assert!(matches!(pt(one_of), ParamType::OneOf(_)));
+    }
+
     #[test]
     fn resolves_component_refs_recursively() {
         let mut components = HashMap::new();
@@ -366,6 +941,99 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn custom_fields_expands_nested_records() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "owner": {"$ref": "https://tx3.land/specs/v1beta0/tii#/$defs/Address"},
+                "terms": {
+                    "type": "object",
+                    "properties": {"deadline": {"type": "integer"}}
+                }
+            }
+        });
+
+        let fields = pt(schema).custom_fields().expect("record has fields");
+        let names: Vec<&str> = fields.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["owner", "terms"]);
+        assert!(matches!(fields[0].1, ParamType::Address));
+
+        let nested = fields[1]
+            .1
+            .custom_fields()
+            .expect("nested record has fields");
+        assert_eq!(nested.len(), 1);
+        assert!(matches!(nested[0].1, ParamType::Integer));
+
+        assert!(pt(json!({"type": "integer"})).custom_fields().is_none());
+    }
+
+    #[test]
+    fn to_json_schema_round_trips_core_types() {
+        let schemas = [
+            json!({"type": "integer"}),
+            json!({"type": "boolean"}),
+            json!({"type": "null"}),
+            json!({"$ref": "https://tx3.land/specs/v1beta0/tii#/$defs/Bytes"}),
+            json!({"$ref": "https://tx3.land/specs/v1beta0/tii#/$defs/Address"}),
+            json!({"$ref": "https://tx3.land/specs/v1beta0/tii#/$defs/UtxoRef"}),
+            json!({"$ref": "https://tx3.land/specs/v1beta0/tii#/$defs/Utxo"}),
+            json!({"$ref": "https://tx3.land/specs/v1beta0/tii#/$defs/AnyAsset"}),
+            json!({"type": "array", "items": {"type": "integer"}}),
+            json!({"type": "array", "prefixItems": [{"type": "integer"}, {"type": "boolean"}]}),
+            json!({"type": "object", "additionalProperties": {"type": "integer"}}),
+            json!({"type": "string", "format": "custom"}),
+        ];
+
+        for schema in schemas {
+            assert_eq!(pt(schema.clone()).to_json_schema(), schema);
+        }
+    }
+
+    #[test]
+    fn to_json_schema_is_stable_for_components() {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let tii =
+            std::fs::read_to_string(format!("{manifest_dir}/tests/fixtures/complex.tii")).unwrap();
+        let tii: Value = serde_json::from_str(&tii).unwrap();
+        let components: HashMap<String, Value> =
+            serde_json::from_value(tii["components"]["schemas"].clone()).unwrap();
+
+        for schema in components.values() {
+            let once = ParamType::from_json_schema(schema, &components).to_json_schema();
+            let twice = pt(once.clone()).to_json_schema();
+            assert_eq!(once, twice);
+        }
+
+        let side = ParamType::from_json_schema(&components["Side"], &components);
+        assert_eq!(side.to_json_schema(), components["Side"]);
+    }
+
+    #[test]
+    fn depth_limit_bounds_the_walk() {
+        let nested =
+            json!({ "type": "array", "items": { "type": "array", "items": { "type": "null" } } });
+        let none = HashMap::new();
+
+        assert!(ParamType::from_json_schema_with_limit(&nested, &none, 2).is_ok());
+        assert!(matches!(
+            ParamType::from_json_schema_with_limit(&nested, &none, 1),
+            Err(Error::SchemaTooDeep(1))
+        ));
+
+        // The infallible entry point degrades a cycle to `Unknown`.
+        let components = HashMap::from([(
+            "Loop".to_string(),
+            json!({ "$ref": "#/components/schemas/Loop" }),
+        )]);
+        let cyclic = json!({ "$ref": "#/components/schemas/Loop" });
+        assert!(matches!(
+            ParamType::from_json_schema(&cyclic, &components),
+            ParamType::Unknown(_)
+        ));
+    }
+
     #[test]
     fn unrecognized_shapes_fall_back_to_unknown() {
         assert!(matches!(pt(json!({"type": "string"})), ParamType::Unknown(_)));
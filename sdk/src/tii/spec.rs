@@ -34,6 +34,14 @@ pub struct TiiFile {
     /// Optional reusable components (schemas, etc.).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub components: Option<Components>,
+
+    /// Optional name of the profile to use when none is selected.
+    #[serde(
+        default,
+        alias = "defaultProfile",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub default_profile: Option<String>,
 }
 
 /// TII version information.
@@ -43,6 +51,15 @@ pub struct TiiFile {
 pub struct TiiInfo {
     /// The TII specification version (e.g., "1.0.0").
     pub version: String,
+
+    /// Optional name of the profile to use when none is selected. The
+    /// top-level `default_profile` takes precedence over this one.
+    #[serde(
+        default,
+        alias = "defaultProfile",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub default_profile: Option<String>,
 }
 
 /// Protocol metadata.
@@ -109,6 +126,137 @@ pub struct Profile {
     /// Party addresses for this profile.
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub parties: HashMap<String, String>,
+
+    /// Optional network the profile targets.
+    ///
+    /// When present, address arguments are checked against it before the
+    /// invocation is turned into a resolve request. A network this SDK
+    /// doesn't know by name is kept as [`Network::Unknown`] and skips the
+    /// check.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<Network>,
+}
+
+/// A Cardano network a profile can target.
+///
+/// Serialized as its name (`"mainnet"`, `"preview"`, `"preprod"`), or as
+/// the network id for a custom network. `"testnet"` reads as a custom
+/// network with the testnet id 0, and any other name as
+/// [`Network::Unknown`].
+///
+/// A custom network with mainnet's id equals [`Network::Mainnet`], and
+/// unknown names compare ignoring case.
+#[derive(Debug, Clone)]
+pub enum Network {
+    /// The Cardano mainnet.
+    Mainnet,
+    /// The preview testnet.
+    Preview,
+    /// The pre-production testnet.
+    Preprod,
+    /// Any other network, by the network id its addresses carry.
+    Custom(u8),
+    /// A network named in the TII that this SDK doesn't know, kept as
+    /// given. Its network id is unknown, so addresses aren't checked
+    /// against it.
+    Unknown(String),
+}
+
+impl Network {
+    /// The network id addresses on this network carry, or `None` for an
+    /// [`Network::Unknown`] network.
+    pub fn network_id(&self) -> Option<u8> {
+        match self {
+            Network::Mainnet => Some(1),
+            Network::Preview | Network::Preprod => Some(0),
+            Network::Custom(id) => Some(*id),
+            Network::Unknown(_) => None,
+        }
+    }
+
+    /// The network as address parsing reports it, if its id is known.
+    pub(crate) fn address_network(&self) -> Option<pallas_addresses::Network> {
+        self.network_id().map(pallas_addresses::Network::from)
+    }
+
+    /// The network this one compares as: a custom network with mainnet's id
+    /// is mainnet.
+    fn canonical(&self) -> &Network {
+        match self {
+            Network::Custom(1) => &Network::Mainnet,
+            other => other,
+        }
+    }
+}
+
+impl PartialEq for Network {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.canonical(), other.canonical()) {
+            (Network::Unknown(a), Network::Unknown(b)) => a.eq_ignore_ascii_case(b),
+            (a, b) => {
+                std::mem::discriminant(a) == std::mem::discriminant(b)
+                    && a.network_id() == b.network_id()
+            }
+        }
+    }
+}
+
+impl Eq for Network {}
+
+impl std::hash::Hash for Network {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let network = self.canonical();
+        std::mem::discriminant(network).hash(state);
+
+        match network {
+            Network::Unknown(name) => name.to_ascii_lowercase().hash(state),
+            other => other.network_id().hash(state),
+        }
+    }
+}
+
+impl std::fmt::Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Network::Mainnet => write!(f, "mainnet"),
+            Network::Preview => write!(f, "preview"),
+            Network::Preprod => write!(f, "preprod"),
+            Network::Custom(id) => write!(f, "network {id}"),
+            Network::Unknown(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl Serialize for Network {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Network::Custom(id) => serializer.serialize_u8(*id),
+            Network::Unknown(name) => serializer.serialize_str(name),
+            named => serializer.serialize_str(&named.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Network {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Id(u8),
+            Name(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Id(id) => Ok(Network::Custom(id)),
+            Raw::Name(name) => match name.to_lowercase().as_str() {
+                "mainnet" => Ok(Network::Mainnet),
+                "preview" => Ok(Network::Preview),
+                "preprod" => Ok(Network::Preprod),
+                "testnet" => Ok(Network::Custom(0)),
+                _ => Ok(Network::Unknown(name)),
+            },
+        }
+    }
 }
 
 /// Components section containing reusable schemas.
@@ -0,0 +1,290 @@
+//! Read-only introspection of TIR payloads.
+//!
+//! The SDK treats TIR as opaque when resolving, but some previews (which
+//! inputs a tx will look for, for instance) need to look inside. The payload
+//! is the CBOR encoding of the tx3 IR, so it is decoded here into a generic
+//! JSON tree rather than into typed IR structs, which keeps this module
+//! tolerant to IR fields it doesn't know about.
+
+use base64::Engine as _;
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::core::{TirEncoding, TirEnvelope};
+use crate::tii::{cbor, Error};
+
+/// The IR placeholder for an absent optional expression.
+const IR_NONE: &str = "None";
+
+/// Decodes a TIR envelope into a JSON tree of its IR, see [`cbor::to_json`].
+///
+/// The error message tells apart content that doesn't match its declared
+/// encoding from bytes that aren't well-formed CBOR (e.g. truncated).
+pub(crate) fn decode(tir: &TirEnvelope) -> Result<Value, Error> {
+    let bytes = match tir.encoding {
+        TirEncoding::Hex => hex::decode(&tir.content).map_err(|e| e.to_string()),
+        TirEncoding::Base64 => base64::engine::general_purpose::STANDARD
+            .decode(&tir.content)
+            .map_err(|e| e.to_string()),
+    };
+
+    let bytes = bytes
+        .map_err(|e| Error::InvalidTir(format!("content is not valid {}: {e}", tir.encoding)))?;
+
+    cbor::to_json(&bytes)
+        .map_err(|e| Error::InvalidTir(format!("content is not well-formed CBOR: {e}")))
+}
+
+/// A query the resolver runs to select UTxOs for one tx input.
+///
+/// Criteria are IR expressions, kept as decoded JSON. Params the invocation
+/// already has values for are substituted in, so a fully specified address
+/// shows up as a plain string; anything else (arithmetic, fees, compiler
+/// computed values) stays an expression tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputQuery {
+    /// The input name as declared in the tx.
+    pub name: String,
+
+    /// The address the UTxOs must be locked at, if constrained.
+    pub address: Option<Value>,
+
+    /// The minimum assets the selected UTxOs must hold, if constrained.
+    pub min_amount: Option<Value>,
+
+    /// A specific UTxO reference the input must spend, if constrained.
+    pub r#ref: Option<Value>,
+
+    /// Whether the input may select more than one UTxO.
+    pub many: bool,
+
+    /// Whether the input is used as collateral.
+    pub collateral: bool,
+}
+
+/// A param the compiled IR expects the resolver to be given.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TirParam {
+    /// The param name, as spelled in the IR.
+    pub name: String,
+
+    /// The IR type, kept as decoded JSON: a plain name such as `"Int"` or
+    /// `"Address"` for primitives, a tree for custom types.
+    pub ty: Value,
+}
+
+/// Collects every input query found in an IR tree, in document order.
+///
+/// An input referenced from several places (e.g. its own declaration and a
+/// change output computed from it) repeats the same query; only the first
+/// occurrence of each name is kept.
+pub(super) fn input_queries(ir: &Value) -> Vec<InputQuery> {
+    let mut out = Vec::new();
+    collect_input_queries(ir, &mut out);
+    out
+}
+
+fn collect_input_queries(node: &Value, out: &mut Vec<InputQuery>) {
+    match node {
+        Value::Object(obj) => {
+            if let Some(query) = obj.get("ExpectInput").and_then(input_query) {
+                if !out.iter().any(|x| x.name == query.name) {
+                    out.push(query);
+                }
+
+                return;
+            }
+
+            obj.values().for_each(|x| collect_input_queries(x, out));
+        }
+        Value::Array(items) => items.iter().for_each(|x| collect_input_queries(x, out)),
+        _ => (),
+    }
+}
+
+/// Reads an `ExpectInput` payload, a `[name, query]` pair.
+fn input_query(node: &Value) -> Option<InputQuery> {
+    let [name, query] = node.as_array()?.as_slice() else {
+        return None;
+    };
+
+    let expr = |key: &str| {
+        query
+            .get(key)
+            .filter(|x| x.as_str() != Some(IR_NONE))
+            .cloned()
+    };
+
+    let flag = |key: &str| query.get(key).and_then(Value::as_bool).unwrap_or(false);
+
+    Some(InputQuery {
+        name: name.as_str()?.to_string(),
+        address: expr("address"),
+        min_amount: expr("min_amount"),
+        r#ref: expr("ref"),
+        many: flag("many"),
+        collateral: flag("collateral"),
+    })
+}
+
+/// An output a tx built from the IR must contain.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ExpectedOutput {
+    /// The address the output pays to.
+    pub address: String,
+
+    /// The lovelace it carries, when the IR sets it to a single known
+    /// amount of the native asset.
+    pub lovelace: Option<u64>,
+}
+
+/// Lists the non-optional outputs of an IR tree whose address is known once
+/// `args` are substituted in.
+///
+/// Outputs to computed addresses can't be matched and are skipped, as are
+/// amounts that are computed (change, fees) or carry other assets.
+pub(crate) fn expected_outputs(ir: &Value, args: &crate::core::ArgMap) -> Vec<ExpectedOutput> {
+    let Some(outputs) = ir.get("outputs").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+
+    let resolved = |expr: &Value| {
+        let mut expr = expr.clone();
+        substitute_params(&mut expr, args);
+        expr
+    };
+
+    let lovelace = |amount: Value| {
+        let [asset] = amount.get("Assets")?.as_array()?.as_slice() else {
+            return None;
+        };
+
+        if asset.get("policy")?.as_str()? != IR_NONE {
+            return None;
+        }
+
+        let amount = asset.get("amount")?;
+        amount.as_u64().or_else(|| amount.get("Number")?.as_u64())
+    };
+
+    outputs
+        .iter()
+        .filter(|x| x.get("optional").and_then(Value::as_bool) != Some(true))
+        .filter_map(|output| {
+            let address = resolved(output.get("address")?);
+
+            Some(ExpectedOutput {
+                address: address.as_str()?.to_string(),
+                lovelace: output.get("amount").map(resolved).and_then(lovelace),
+            })
+        })
+        .collect()
+}
+
+/// Replaces `ExpectValue` param placeholders with the matching arg.
+///
+/// Placeholders for params without a value are left untouched.
+pub(super) fn substitute_params(node: &mut Value, args: &crate::core::ArgMap) {
+    if let Some(name) = expected_param(node) {
+        // Args are spelled per the invocation's case policy, so the IR name
+        // may differ from the key only in case.
+        let value = args.get(name).or_else(|| {
+            args.iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value)
+        });
+
+        if let Some(value) = value {
+            *node = value.clone();
+        }
+
+        return;
+    }
+
+    match node {
+        Value::Object(obj) => obj.values_mut().for_each(|x| substitute_params(x, args)),
+        Value::Array(items) => items.iter_mut().for_each(|x| substitute_params(x, args)),
+        _ => (),
+    }
+}
+
+/// Collects the (lowercased) names of every param the IR tree reads.
+pub(super) fn referenced_params(node: &Value) -> HashSet<String> {
+    let mut out = HashSet::new();
+    collect_referenced_params(node, &mut out);
+    out
+}
+
+fn collect_referenced_params(node: &Value, out: &mut HashSet<String>) {
+    if let Some(name) = expected_param(node) {
+        out.insert(name.to_lowercase());
+        return;
+    }
+
+    match node {
+        Value::Object(obj) => obj.values().for_each(|x| collect_referenced_params(x, out)),
+        Value::Array(items) => items.iter().for_each(|x| collect_referenced_params(x, out)),
+        _ => (),
+    }
+}
+
+/// Collects every param the IR tree reads, in document order.
+///
+/// Unlike [`referenced_params`], names keep their IR spelling and carry the
+/// type they are expected with; a param read in several places is listed
+/// once.
+pub(super) fn params(node: &Value) -> Vec<TirParam> {
+    let mut out = Vec::new();
+    collect_params(node, &mut out);
+    out
+}
+
+fn collect_params(node: &Value, out: &mut Vec<TirParam>) {
+    if let Some(name) = expected_param(node) {
+        if !out.iter().any(|x| x.name == name) {
+            let ty = node["EvalParam"]["ExpectValue"]
+                .get(1)
+                .cloned()
+                .unwrap_or(Value::Null);
+
+            out.push(TirParam {
+                name: name.to_string(),
+                ty,
+            });
+        }
+
+        return;
+    }
+
+    match node {
+        Value::Object(obj) => obj.values().for_each(|x| collect_params(x, out)),
+        Value::Array(items) => items.iter().for_each(|x| collect_params(x, out)),
+        _ => (),
+    }
+}
+
+/// The param name of an `{"EvalParam": {"ExpectValue": [name, type]}}` node.
+fn expected_param(node: &Value) -> Option<&str> {
+    node.get("EvalParam")?.get("ExpectValue")?.get(0)?.as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_fixtures::hex_tir;
+
+    #[test]
+    fn decodes_indefinite_containers_and_bytes() {
+        // {_ "a": [_ 1, -2], "b": h'cafe'}
+        let value = decode(&hex_tir("bf61619f0121ff616242cafeff")).unwrap();
+        assert_eq!(value, json!({ "a": [1, -2], "b": "cafe" }));
+
+        assert!(matches!(decode(&hex_tir("zz")), Err(Error::InvalidTir(_))));
+        assert!(matches!(
+            decode(&hex_tir("9f01")),
+            Err(Error::InvalidTir(_))
+        ));
+    }
+}
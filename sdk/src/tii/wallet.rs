@@ -0,0 +1,50 @@
+//! The seam between a connected wallet and an [`Invocation`].
+//!
+//! Browser dApps and desktop apps alike hold the user's addresses, UTxOs and
+//! keys behind a wallet connector. [`Wallet`] is the shape the SDK expects of
+//! such a connector, so [`Invocation::bind_wallet`] can fill the args a wallet
+//! knows instead of every app re-implementing that glue.
+//!
+//! [`Invocation`]: crate::tii::Invocation
+//! [`Invocation::bind_wallet`]: crate::tii::Invocation::bind_wallet
+
+use crate::trp::{TxEnvelope, TxInputRef, TxWitness};
+
+/// A connected wallet.
+///
+/// A connector says which protocol parties the wallet acts as and which UTxO
+/// it offers as collateral; neither has a safe default to guess from.
+pub trait Wallet {
+    /// The address change goes to, also the wallet's default address.
+    fn change_address(&self) -> String;
+
+    /// The UTxOs the wallet can spend.
+    fn utxos(&self) -> Vec<TxInputRef>;
+
+    /// Signs a resolved transaction, returning the witnesses to submit it
+    /// with.
+    fn sign(
+        &self,
+        tx: &TxEnvelope,
+    ) -> Result<Vec<TxWitness>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// The address the wallet holds for a protocol party, if it acts as
+    /// that party.
+    ///
+    /// Defaults to `None` for every party: filling a counterparty (say, the
+    /// receiver of a transfer) with the user's own address would build a
+    /// valid tx paying the user. A single-account wallet acting as the
+    /// sender returns its change address for `"sender"` only.
+    fn address_for(&self, party: &str) -> Option<String> {
+        let _ = party;
+        None
+    }
+
+    /// The UTxO to offer as collateral for script txs.
+    ///
+    /// Collateral must hold only ADA and enough of it to cover a failing
+    /// script, and is forfeited if the script does fail, so there's no
+    /// default: an arbitrary UTxO could hold tokens or too little ADA.
+    /// Return `None` if the wallet has no suitable UTxO.
+    fn collateral(&self) -> Option<TxInputRef>;
+}
@@ -0,0 +1,114 @@
+//! Opt-in in-memory cache of resolve results.
+//!
+//! Entries are keyed by a Blake2b-256 hash of the serialized resolve params.
+//! Args are a sorted JSON map, so the same TIR and args always hash the same
+//! regardless of the order they were set in. The cache is shared behind an
+//! `Arc`, so clones of a [`super::Client`] hit the same entries.
+
+use lru::LruCache;
+use pallas_crypto::hash::{Hash, Hasher};
+use serde_json::Value;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+use crate::trp::TxEnvelope;
+
+/// Cache key for a set of resolve params.
+pub(crate) type CacheKey = Hash<32>;
+
+/// LRU cache of resolved transactions.
+pub(crate) struct ResolveCache {
+    entries: Mutex<LruCache<CacheKey, TxEnvelope>>,
+}
+
+impl ResolveCache {
+    pub(crate) fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Computes the key for resolve params already serialized to JSON.
+    pub(crate) fn key(params: &Value) -> CacheKey {
+        let bytes = serde_json::to_vec(params).unwrap_or_default();
+        Hasher::<256>::hash(&bytes)
+    }
+
+    pub(crate) fn get(&self, key: &CacheKey) -> Option<TxEnvelope> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    pub(crate) fn put(&self, key: CacheKey, tx: TxEnvelope) {
+        self.entries.lock().unwrap().put(key, tx);
+    }
+
+    pub(crate) fn remove(&self, key: &CacheKey) {
+        self.entries.lock().unwrap().pop(key);
+    }
+
+    pub(crate) fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+impl std::fmt::Debug for ResolveCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entries = self.entries.lock().unwrap();
+
+        f.debug_struct("ResolveCache")
+            .field("len", &entries.len())
+            .field("capacity", &entries.cap())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn tx(hash: &str) -> TxEnvelope {
+        TxEnvelope {
+            hash: hash.to_string(),
+            tx: String::new(),
+        }
+    }
+
+    #[test]
+    fn key_ignores_arg_insertion_order() {
+        let mut a = serde_json::Map::new();
+        a.insert("x".to_string(), json!(1));
+        a.insert("y".to_string(), json!(2));
+
+        let mut b = serde_json::Map::new();
+        b.insert("y".to_string(), json!(2));
+        b.insert("x".to_string(), json!(1));
+
+        let key = |args| ResolveCache::key(&json!({ "args": args, "tir": "t" }));
+        assert_eq!(key(a.clone()), key(b));
+
+        a.insert("x".to_string(), json!(3));
+        assert_ne!(key(a.clone()), key(a.clone().into_iter().take(1).collect()));
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = ResolveCache::new(NonZeroUsize::new(2).unwrap());
+        let keys: Vec<_> = (0..3).map(|i| ResolveCache::key(&json!(i))).collect();
+
+        cache.put(keys[0], tx("a"));
+        cache.put(keys[1], tx("b"));
+        assert_eq!(cache.get(&keys[0]).unwrap().hash, "a");
+
+        cache.put(keys[2], tx("c"));
+        assert!(cache.get(&keys[1]).is_none());
+        assert!(cache.get(&keys[0]).is_some());
+
+        cache.remove(&keys[0]);
+        assert!(cache.get(&keys[0]).is_none());
+
+        cache.clear();
+        assert!(cache.get(&keys[2]).is_none());
+    }
+}
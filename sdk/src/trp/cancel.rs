@@ -0,0 +1,61 @@
+//! Cancellation of in-flight TRP requests.
+//!
+//! A [`CancellationToken`] is a shared flag: clones observe the same state,
+//! so a UI can hand one clone to the request and keep another to fire when
+//! the user navigates away. Firing it drops the pending request future,
+//! which aborts the underlying HTTP request.
+
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// A cloneable handle that cancels the requests it was passed to.
+///
+/// # Example
+///
+/// ```ignore
+/// use tx3_sdk::trp::CancellationToken;
+///
+/// let token = CancellationToken::new();
+/// let pending = client.resolve_with_cancel(params, token.clone());
+///
+/// // elsewhere, e.g. when the view is closed
+/// token.cancel();
+/// ```
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+    state: Arc<watch::Sender<bool>>,
+}
+
+impl CancellationToken {
+    /// Creates a token that hasn't been cancelled.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    /// Cancels every request this token (or a clone of it) was passed to.
+    /// Cancelling twice is a no-op.
+    pub fn cancel(&self) {
+        self.state.send_replace(true);
+    }
+
+    /// Whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        *self.state.borrow()
+    }
+
+    /// Completes once the token is cancelled.
+    pub(crate) async fn cancelled(&self) {
+        let mut rx = self.state.subscribe();
+
+        // The sender lives in `self`, so the channel can't close while waiting
+        let _ = rx.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
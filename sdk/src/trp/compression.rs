@@ -0,0 +1,39 @@
+//! Opt-in gzip compression of request bodies.
+//!
+//! Gzip members are built from miniz_oxide's raw deflate plus a CRC-32
+//! trailer, which is all the one-shot compression of a request body needs.
+
+/// Compresses `data` into a gzip member (RFC 1952).
+pub(crate) fn gzip(data: &[u8]) -> Vec<u8> {
+    const HEADER: [u8; 10] = [0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+    const CRC: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+
+    let mut out = HEADER.to_vec();
+    out.extend(miniz_oxide::deflate::compress_to_vec(data, 6));
+    out.extend(CRC.checksum(data).to_le_bytes());
+    out.extend((data.len() as u32).to_le_bytes());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gzip_produces_a_valid_member() {
+        let data = br#"{"jsonrpc":"2.0","method":"trp.resolve"}"#.repeat(10);
+        let member = gzip(&data);
+
+        assert_eq!(&member[..3], &[0x1f, 0x8b, 8]);
+        assert!(member.len() < data.len());
+
+        let deflated = &member[10..member.len() - 8];
+        assert_eq!(
+            miniz_oxide::inflate::decompress_to_vec(deflated).unwrap(),
+            data
+        );
+
+        let size = u32::from_le_bytes(member[member.len() - 4..].try_into().unwrap());
+        assert_eq!(size as usize, data.len());
+    }
+}
@@ -0,0 +1,64 @@
+//! Client-side rate limiting of TRP requests.
+//!
+//! Requests are spaced evenly, one every `1 / per_second`: each caller
+//! reserves the next free slot and sleeps until it. The slot is shared behind
+//! an `Arc`, so concurrent tasks and clones of a [`super::Client`] draw from
+//! the same budget.
+
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Evenly spaced request slots.
+pub(crate) struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// A limiter allowing `per_second` requests per second.
+    pub(crate) fn new(per_second: std::num::NonZeroU32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / per_second.get(),
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Waits until the caller may send its request.
+    pub(crate) async fn acquire(&self) {
+        let slot = {
+            let mut next = self.next.lock().unwrap();
+            let slot = (*next).max(Instant::now());
+            *next = slot + self.interval;
+            slot
+        };
+
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+impl std::fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn spaces_requests_by_the_interval() {
+        let limiter = RateLimiter::new(std::num::NonZeroU32::new(20).unwrap());
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+
+        // The first slot is immediate, the next two wait 50ms each
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
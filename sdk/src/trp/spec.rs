@@ -9,10 +9,11 @@
 //! - Monitoring transaction status and lifecycle
 //! - Querying pending and inflight transaction queues
 
+use pallas_traverse::Era;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use crate::core::{ArgMap, BytesEnvelope, EnvMap, TirEnvelope};
+use crate::core::{ArgMap, BytesEnvelope, EnvMap, TirEnvelope, UtxoRef};
 
 /// Parameters for submitting a signed transaction to the network.
 ///
@@ -53,6 +54,46 @@ pub struct SubmitParams {
     pub witnesses: Vec<TxWitness>,
 }
 
+impl SubmitParams {
+    /// Builds submit parameters for a transaction whose CBOR already carries
+    /// its full witness set.
+    ///
+    /// The bytes are hex-encoded into the `tx` envelope and `witnesses` is left
+    /// empty, as the server has nothing to splice into the witness set.
+    pub fn from_signed_cbor(cbor: &[u8]) -> Self {
+        Self::from_signed_cbor_with_witnesses(cbor, Vec::new())
+    }
+
+    /// Builds submit parameters for a transaction whose CBOR is missing some
+    /// witnesses, sent alongside for the server to add to its witness set.
+    ///
+    /// The bytes are hex-encoded into the `tx` envelope, as in
+    /// [`SubmitParams::from_signed_cbor`].
+    pub fn from_signed_cbor_with_witnesses(cbor: &[u8], witnesses: Vec<TxWitness>) -> Self {
+        SubmitParams {
+            tx: BytesEnvelope {
+                content: hex::encode(cbor),
+                content_type: "hex".to_string(),
+            },
+            witnesses,
+        }
+    }
+
+    /// The hash of the transaction, computed locally from its body.
+    ///
+    /// `None` if the tx isn't hex-encoded or doesn't decode.
+    pub(super) fn tx_hash(&self) -> Option<String> {
+        if self.tx.content_type != "hex" {
+            return None;
+        }
+
+        let cbor = hex::decode(&self.tx.content).ok()?;
+        let tx = decode_tx(&cbor).ok()?;
+
+        Some(tx.hash().to_string())
+    }
+}
+
 /// A resolved transaction envelope returned by the TRP resolver.
 ///
 /// This structure contains a fully resolved UTxO transaction ready for signing
@@ -73,19 +114,286 @@ pub struct TxEnvelope {
     pub tx: String,
 }
 
+impl TxEnvelope {
+    /// Decodes the transaction CBOR and returns the ledger era it belongs to.
+    ///
+    /// The era is inferred from which era's encoding the bytes decode as,
+    /// newest first, so a tx valid under several eras reports the latest one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidTxCbor` if `tx` is not hex or doesn't decode as
+    /// a transaction of any known era.
+    pub fn era(&self) -> Result<Era, super::Error> {
+        let cbor = self.cbor()?;
+
+        Ok(decode_tx(&cbor)?.era())
+    }
+
+    /// Checks that the resolved tx carries out what the invocation asked
+    /// for, guarding against a gateway that tampers with the tx.
+    ///
+    /// Every output of the tx template whose address is a known arg (a party,
+    /// typically) must be paid by its own output of the tx, with at least the
+    /// lovelace the template sets it to when that amount is a known arg too;
+    /// one tx output never covers two template outputs. Outputs to computed
+    /// addresses and computed amounts (change, fees) can't be checked and are
+    /// skipped, so passing doesn't prove the tx is exactly what the template
+    /// describes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::TxMismatch` naming an expected output that is missing
+    /// or whose address doesn't parse, `Error::InvalidTxCbor` if the tx can't
+    /// be decoded, and
+    /// `Error::InvalidInvocation` if the invocation can't build a resolve
+    /// request.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let tx = client.resolve(invocation.effective_resolve_params()?).await?;
+    /// tx.verify_against(&invocation)?;
+    /// ```
+    pub fn verify_against(&self, invocation: &crate::tii::Invocation) -> Result<(), super::Error> {
+        let request = invocation
+            .effective_resolve_params()
+            .map_err(super::Error::InvalidInvocation)?;
+
+        let ir = crate::tii::tir::decode(&request.tir)
+            .map_err(|e| super::Error::InvalidTirBytes(e.to_string(), None))?;
+
+        let cbor = self.cbor()?;
+        let tx = decode_tx(&cbor)?;
+
+        let mut outputs: Vec<_> = tx
+            .outputs()
+            .iter()
+            .filter_map(|x| Some((x.address().ok()?.to_vec(), x.value().coin())))
+            .collect();
+
+        // Matching the largest amounts first, each to the smallest output
+        // that covers it, finds a one-to-one match whenever there is one.
+        let mut expected = crate::tii::tir::expected_outputs(&ir, &request.args);
+        expected.sort_by_key(|x| std::cmp::Reverse(x.lovelace));
+
+        for expected in expected {
+            let Ok(address) = expected.address.parse::<pallas_addresses::Address>() else {
+                return Err(super::Error::TxMismatch(format!(
+                    "expected output address {} is not a valid address",
+                    expected.address
+                )));
+            };
+
+            let address = address.to_vec();
+            let min = expected.lovelace.unwrap_or(0);
+
+            let matched = outputs
+                .iter()
+                .enumerate()
+                .filter(|(_, (a, coin))| *a == address && *coin >= min)
+                .min_by_key(|(_, (_, coin))| *coin)
+                .map(|(i, _)| i);
+
+            let Some(i) = matched else {
+                let amount = expected
+                    .lovelace
+                    .map(|x| format!(" with {x} lovelace"))
+                    .unwrap_or_default();
+
+                return Err(super::Error::TxMismatch(format!(
+                    "no output pays {}{amount}",
+                    expected.address
+                )));
+            };
+
+            outputs.swap_remove(i);
+        }
+
+        Ok(())
+    }
+
+    /// The raw transaction CBOR, decoded from hex.
+    fn cbor(&self) -> Result<Vec<u8>, super::Error> {
+        hex::decode(&self.tx).map_err(|e| super::Error::InvalidTxCbor(e.to_string()))
+    }
+
+    /// Checks locally that the transaction is a Conway-era transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidTxCbor` if the CBOR can't be decoded, or
+    /// `Error::WrongTxEra` naming the era it was decoded as otherwise.
+    pub fn require_conway(&self) -> Result<(), super::Error> {
+        match self.era()? {
+            Era::Conway => Ok(()),
+            era => Err(super::Error::WrongTxEra(era)),
+        }
+    }
+}
+
+fn decode_tx(cbor: &[u8]) -> Result<pallas_traverse::MultiEraTx<'_>, super::Error> {
+    pallas_traverse::MultiEraTx::decode(cbor)
+        .map_err(|e| super::Error::InvalidTxCbor(e.to_string()))
+}
+
+/// A reference to a UTxO consumed by a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TxInputRef {
+    /// Hash of the transaction that produced the UTxO (hex-encoded).
+    pub hash: String,
+
+    /// Index of the UTxO among that transaction's outputs.
+    pub index: u64,
+}
+
+impl std::fmt::Display for TxInputRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}#{}", self.hash, self.index)
+    }
+}
+
+/// A resolved transaction along with the facts most callers read from it.
+///
+/// Returned by `Client::resolve_detailed`, or built from any envelope with
+/// [`ResolvedTx::from_envelope`].
+///
+/// # Fields
+///
+/// * `envelope` - The envelope as returned by the resolver
+/// * `fee` - The fee declared in the tx body, if any
+/// * `inputs` - The UTxOs spent by the tx, in body order
+#[derive(Debug, Clone)]
+pub struct ResolvedTx {
+    /// The envelope as returned by the resolver.
+    pub envelope: TxEnvelope,
+
+    /// The fee declared in the tx body, in lovelace.
+    ///
+    /// `None` for eras whose body has no fee field.
+    pub fee: Option<u64>,
+
+    /// The UTxOs spent by the tx, in body order.
+    ///
+    /// Collateral and reference inputs are not included.
+    pub inputs: Vec<TxInputRef>,
+}
+
+impl ResolvedTx {
+    /// Decodes the envelope's CBOR to extract the fee and inputs.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidTxCbor` if `tx` is not hex or doesn't decode as
+    /// a transaction of any known era.
+    pub fn from_envelope(envelope: TxEnvelope) -> Result<Self, super::Error> {
+        let cbor = envelope.cbor()?;
+        let tx = decode_tx(&cbor)?;
+
+        let inputs = tx
+            .inputs()
+            .iter()
+            .map(|x| TxInputRef {
+                hash: x.hash().to_string(),
+                index: x.index(),
+            })
+            .collect();
+
+        Ok(Self {
+            fee: tx.fee(),
+            inputs,
+            envelope,
+        })
+    }
+
+    /// The transaction hash (hex-encoded).
+    pub fn hash(&self) -> &str {
+        &self.envelope.hash
+    }
+
+    /// The CBOR-encoded transaction as a hex string.
+    pub fn hex(&self) -> &str {
+        &self.envelope.tx
+    }
+}
+
 /// Response from a successful transaction submission.
 ///
 /// After submitting a signed transaction, the TRP server returns this structure
 /// containing the transaction hash, which can be used to track the transaction status.
 ///
+/// Gateways differ in what else they report, so the optional details are
+/// normalized into `Option` fields and read through the accessors.
+///
 /// # Fields
 ///
 /// * `hash` - The submitted transaction hash
+/// * `accepted` - Whether the gateway accepted the tx, if reported
+/// * `cbor` - The tx as relayed by the gateway (hex-encoded), if reported
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmitResponse {
     /// The transaction hash that was submitted.
-    #[serde(rename = "hash")]
+    #[serde(rename = "hash", alias = "txHash")]
     pub hash: String,
+
+    /// Whether the gateway accepted the tx, if it reports it.
+    ///
+    /// `Client::submit` turns `Some(false)` into
+    /// `Error::SubmitNotAccepted`, so the responses it returns never carry it.
+    #[serde(rename = "accepted", default, skip_serializing_if = "Option::is_none")]
+    pub accepted: Option<bool>,
+
+    /// The tx as relayed by the gateway, with its witnesses spliced in, if
+    /// it echoes it back.
+    #[serde(rename = "cbor", default, skip_serializing_if = "Option::is_none")]
+    pub cbor: Option<String>,
+}
+
+impl SubmitResponse {
+    /// The submitted transaction hash (hex-encoded).
+    pub fn tx_hash(&self) -> &str {
+        &self.hash
+    }
+
+    /// Whether the gateway accepted the tx.
+    ///
+    /// A gateway that doesn't report it accepted the tx by answering
+    /// successfully, so this is `true` unless it says otherwise.
+    pub fn accepted(&self) -> bool {
+        self.accepted.unwrap_or(true)
+    }
+
+    /// The tx as relayed by the gateway (hex-encoded), if it echoes it back.
+    pub fn cbor(&self) -> Option<&str> {
+        self.cbor.as_deref()
+    }
+}
+
+impl std::fmt::Display for SubmitResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.hash)
+    }
+}
+
+/// The result of a `trp.resolve` call, including any warnings.
+///
+/// Gateways may resolve successfully while flagging soft issues, such as
+/// falling back to a different collateral. Those arrive next to the tx, in
+/// an optional `warnings` list.
+///
+/// # Fields
+///
+/// * `envelope` - The resolved transaction
+/// * `warnings` - Non-fatal warnings the gateway reported, if any
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolveResponse {
+    /// The resolved transaction.
+    #[serde(flatten)]
+    pub envelope: TxEnvelope,
+
+    /// Non-fatal warnings the gateway reported.
+    #[serde(rename = "warnings", default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 /// A cryptographic witness (signature) for a transaction.
@@ -114,6 +422,57 @@ pub struct TxWitness {
     pub witness_type: WitnessType,
 }
 
+/// Length of an Ed25519 public key, in bytes.
+const VKEY_LENGTH: usize = 32;
+
+/// Length of an Ed25519 signature, in bytes.
+const SIGNATURE_LENGTH: usize = 64;
+
+impl TxWitness {
+    /// Builds a VKey witness from a raw Ed25519 public key and signature.
+    ///
+    /// Both are hex-encoded into their envelopes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidWitness` if the key is not 32 bytes or the
+    /// signature is not 64 bytes.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use tx3_sdk::trp::TxWitness;
+    ///
+    /// let witness = TxWitness::vkey(&public_key, &signature)?;
+    /// ```
+    pub fn vkey(public_key: &[u8], signature: &[u8]) -> Result<Self, super::Error> {
+        if public_key.len() != VKEY_LENGTH {
+            return Err(super::Error::InvalidWitness(format!(
+                "public key must be {VKEY_LENGTH} bytes, got {}",
+                public_key.len()
+            )));
+        }
+
+        if signature.len() != SIGNATURE_LENGTH {
+            return Err(super::Error::InvalidWitness(format!(
+                "signature must be {SIGNATURE_LENGTH} bytes, got {}",
+                signature.len()
+            )));
+        }
+
+        let hex = |bytes: &[u8]| BytesEnvelope {
+            content: hex::encode(bytes),
+            content_type: "hex".to_string(),
+        };
+
+        Ok(TxWitness {
+            key: hex(public_key),
+            signature: hex(signature),
+            witness_type: WitnessType::VKey,
+        })
+    }
+}
+
 /// Type of transaction witness.
 ///
 /// Identifies the witness type for a transaction signature. Currently,
@@ -395,6 +754,7 @@ pub struct PeekInflightResponse {
 /// * `tir` - The Transaction Intermediate Representation envelope containing the template
 /// * `args` - Arguments to populate the template parameters
 /// * `env` - Optional environment variables for resolution context
+/// * `extra` - Additional top-level fields, for gateway options not yet typed here
 ///
 /// # Example
 ///
@@ -402,18 +762,34 @@ pub struct PeekInflightResponse {
 /// use tx3_sdk::trp::ResolveParams;
 /// use tx3_sdk::core::TirEnvelope;
 ///
-/// let params = ResolveParams {
-///     tir: TirEnvelope {
-///         content: "a10081825820...".to_string(),
-///         encoding: tx3_sdk::core::TirEncoding::Hex,
-///         version: "v1beta0".to_string(),
-///     },
-///     args: serde_json::Map::new(),
-///     env: None,
+/// let tir = TirEnvelope {
+///     content: "a10081825820...".to_string(),
+///     encoding: tx3_sdk::core::TirEncoding::Hex,
+///     version: "v1beta0".to_string(),
 /// };
+///
+/// let params = ResolveParams::new(tir, serde_json::Map::new());
 /// ```
+///
+/// Non-exhaustive so request options can be added without breaking callers:
+/// build one with [`ResolveParams::new`] and the `with_*` setters.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct ResolveParams {
+    /// Additional top-level fields sent alongside the typed ones.
+    ///
+    /// An escape hatch for experimental gateway options (e.g.
+    /// `costModelOverride`) that this SDK has no typed field for yet. Typed
+    /// fields take precedence: a key here naming a typed field (`args`, `tir`,
+    /// `env`, ...) is skipped when the request is serialized.
+    #[serde(
+        flatten,
+        default,
+        skip_serializing_if = "serde_json::Map::is_empty",
+        serialize_with = "serialize_extra"
+    )]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+
     /// Arguments to populate the transaction template parameters.
     #[serde(rename = "args")]
     pub args: ArgMap,
@@ -425,6 +801,177 @@ pub struct ResolveParams {
     /// Optional environment variables for transaction resolution.
     #[serde(rename = "env", skip_serializing_if = "Option::is_none")]
     pub env: Option<EnvMap>,
+
+    /// Hint for how the resolver should pick UTxOs for the tx inputs.
+    ///
+    /// Omitted from the request when left to the resolver.
+    #[serde(
+        rename = "selectionStrategy",
+        default,
+        skip_serializing_if = "SelectionStrategy::is_auto"
+    )]
+    pub selection_strategy: SelectionStrategy,
+
+    /// The chain point to resolve against, for gateways that keep historical
+    /// UTxO sets.
+    ///
+    /// `None` resolves against the tip.
+    #[serde(rename = "at", default, skip_serializing_if = "Option::is_none")]
+    pub at: Option<ResolvePoint>,
+
+    /// Transaction metadata to attach, keyed by label.
+    ///
+    /// Omitted from the request when empty.
+    #[serde(
+        rename = "metadata",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub metadata: BTreeMap<u64, serde_json::Value>,
+
+    /// The UTxO the resolver should spend to pay the fee, for wallets that
+    /// keep a dedicated fee UTxO.
+    ///
+    /// `None` leaves the choice to the resolver.
+    #[serde(rename = "feeInput", default, skip_serializing_if = "Option::is_none")]
+    pub fee_input: Option<UtxoRef>,
+}
+
+/// Wire names of the typed [`ResolveParams`] fields, which `extra` can't set.
+pub(crate) const TYPED_RESOLVE_FIELDS: [&str; 7] = [
+    "args",
+    "tir",
+    "env",
+    "selectionStrategy",
+    "at",
+    "metadata",
+    "feeInput",
+];
+
+/// Serializes `extra` without the keys of typed fields, so they aren't sent
+/// twice.
+fn serialize_extra<S>(
+    extra: &serde_json::Map<String, serde_json::Value>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_map(
+        extra
+            .iter()
+            .filter(|(key, _)| !TYPED_RESOLVE_FIELDS.contains(&key.as_str())),
+    )
+}
+
+impl ResolveParams {
+    /// Creates resolve params from a TIR and its args, leaving every other
+    /// field to the resolver.
+    ///
+    /// For callers with their own arg handling that don't go through
+    /// [`crate::tii::Invocation::into_resolve_request`]. The args are sent
+    /// as given, with no coercion or validation against the TII.
+    pub fn new(tir: TirEnvelope, args: ArgMap) -> Self {
+        Self {
+            extra: serde_json::Map::new(),
+            args,
+            tir,
+            env: None,
+            selection_strategy: SelectionStrategy::default(),
+            at: None,
+            metadata: BTreeMap::new(),
+            fee_input: None,
+        }
+    }
+
+    /// Sets the environment sent alongside the args (builder pattern).
+    pub fn with_env(mut self, env: EnvMap) -> Self {
+        self.env = Some(env);
+        self
+    }
+
+    /// Sets an extra top-level field, see [`ResolveParams::extra`] (builder
+    /// pattern).
+    ///
+    /// Keys naming a typed field are ignored.
+    pub fn with_extra(mut self, key: &str, value: serde_json::Value) -> Self {
+        if !TYPED_RESOLVE_FIELDS.contains(&key) {
+            self.extra.insert(key.to_string(), value);
+        }
+
+        self
+    }
+
+    /// Sets how the resolver should pick inputs (builder pattern).
+    pub fn with_selection_strategy(mut self, strategy: SelectionStrategy) -> Self {
+        self.selection_strategy = strategy;
+        self
+    }
+
+    /// Resolves against `point` instead of the tip (builder pattern).
+    pub fn with_at(mut self, point: ResolvePoint) -> Self {
+        self.at = Some(point);
+        self
+    }
+
+    /// Attaches transaction metadata under `label` (builder pattern).
+    ///
+    /// Sent as given; [`crate::tii::Invocation::with_metadata`] checks the
+    /// value against the ledger's metadata rules first.
+    pub fn with_metadata(mut self, label: u64, value: serde_json::Value) -> Self {
+        self.metadata.insert(label, value);
+        self
+    }
+
+    /// Designates the UTxO that pays the fee (builder pattern).
+    pub fn with_fee_input(mut self, utxo: UtxoRef) -> Self {
+        self.fee_input = Some(utxo);
+        self
+    }
+}
+
+/// A chain point a resolve runs against, see [`ResolveParams::at`].
+///
+/// # Fields
+///
+/// * `slot` - The slot to resolve at
+/// * `block_hash` - The block at that slot, pinning the fork when known
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolvePoint {
+    /// The slot to resolve at.
+    #[serde(rename = "slot")]
+    pub slot: u64,
+
+    /// The hash of the block at `slot` (hex-encoded), if known.
+    #[serde(rename = "blockHash", default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<String>,
+}
+
+/// How the resolver should pick UTxOs to cover the tx inputs.
+///
+/// A hint: resolvers that don't implement a strategy fall back to their own
+/// selection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionStrategy {
+    /// Let the resolver choose.
+    #[default]
+    Auto,
+
+    /// Prefer the UTxOs holding the most value, keeping inputs few.
+    LargestFirst,
+
+    /// Prefer the UTxOs holding the least value, consolidating dust.
+    SmallestFirst,
+
+    /// Use as few inputs as possible, keeping the tx small and the fee low.
+    MinimizeInputs,
+}
+
+impl SelectionStrategy {
+    fn is_auto(&self) -> bool {
+        matches!(self, SelectionStrategy::Auto)
+    }
 }
 
 /// Diagnostic information about the search space for input resolution.
@@ -536,6 +1083,44 @@ pub struct InputNotResolvedDiagnostic {
     pub search_space: SearchSpaceDiagnostic,
 }
 
+impl InputNotResolvedDiagnostic {
+    /// Returns actionable guidance for the failed query, naming the input
+    /// and the constraint that would help narrow or satisfy it.
+    pub fn hint(&self) -> String {
+        let query = &self.query;
+        let address = query.address.as_deref().unwrap_or("the queried address");
+
+        if !query.refs.is_empty() {
+            return format!(
+                "check that the UTxO refs given to input `{}` ({}) exist and are unspent",
+                self.name,
+                query.refs.join(", ")
+            );
+        }
+
+        if query.min_amount.is_empty() {
+            return format!(
+                "input `{}` has no `min_amount` or `ref` constraint, add one so the resolver can target a UTxO at {address}",
+                self.name
+            );
+        }
+
+        let mut assets: Vec<_> = query
+            .min_amount
+            .iter()
+            .map(|(asset, amount)| format!("{amount} {asset}"))
+            .collect();
+        assets.sort();
+
+        format!(
+            "no UTxO at {address} holds {} for input `{}` ({} candidates matched), fund the address or relax `min_amount`, or pin a specific UTxO with `ref`",
+            assets.join(" + "),
+            self.name,
+            self.search_space.matched.len()
+        )
+    }
+}
+
 /// Diagnostic for a missing transaction argument.
 ///
 /// Returned when a required argument is not provided for a transaction
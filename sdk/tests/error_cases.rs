@@ -50,6 +50,7 @@ fn create_trp_client() -> Option<Client> {
             } else {
                 Some(headers)
             },
+            ..Default::default()
         })
     })
 }
@@ -65,16 +66,14 @@ async fn test_trp_resolve_invalid_tir() {
         return;
     };
 
-    let invalid_params = ResolveParams {
-        tir: TirEnvelope {
-            content: "00".to_string(),
-            encoding: TirEncoding::Hex,
-            version: "v1beta0".to_string(),
-        },
-        args: serde_json::Map::new(),
-        env: None,
+    let tir = TirEnvelope {
+        content: "00".to_string(),
+        encoding: TirEncoding::Hex,
+        version: "v1beta0".to_string(),
     };
 
+    let invalid_params = ResolveParams::new(tir, serde_json::Map::new());
+
     let result = client.resolve(invalid_params).await;
 
     match result {
@@ -78,6 +78,7 @@ fn trp_options() -> Option<ClientOptions> {
         } else {
             Some(headers)
         },
+        ..Default::default()
     })
 }
 